@@ -1,13 +1,34 @@
 // Web API モジュール（標準ライブラリベース）
 
+use brotli::CompressorWriter;
+use crate::ast::Value;
 use crate::jit::JitCompiler;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::{Deserialize, Serialize};
-use std::io::prelude::*;
+use std::collections::HashMap;
+use std::io::{self, prelude::*};
 use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
+/// keep-alive接続がアイドル状態のまま放置された場合に切断するまでの既定時間
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// このバイト数未満のレスポンス本文は圧縮のオーバーヘッドの方が大きいため素通しする
+const COMPRESSION_MIN_BYTES: usize = 256;
+
+/// `/api/stats/stream`でイベントが届かない場合に、既定でkeep-aliveコメント行を送る間隔
+const DEFAULT_STATS_STREAM_INTERVAL: Duration = Duration::from_millis(500);
+
+/// リクエストボディの既定の最大サイズ（1 MiB）。これを超えると413を返す
+const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// ヘッダー+ボディの読み込みに許す既定の制限時間。これを超えると408を返す
+const DEFAULT_SLOW_REQUEST_DEADLINE: Duration = Duration::from_secs(5);
+
 /// APIレスポンス用のJIT統計情報
 #[derive(Serialize, Debug)]
 pub struct ApiJitStats {
@@ -29,7 +50,7 @@ pub struct ExecuteRequest {
 /// 式実行レスポンス
 #[derive(Serialize, Debug)]
 pub struct ExecuteResponse {
-    pub result: i64,
+    pub result: Value,
     pub execution_time_ns: u64,
     pub was_jit_compiled: bool,
     pub message: Option<String>,
@@ -58,8 +79,195 @@ pub struct ErrorResponse {
     pub details: Option<String>,
 }
 
-/// アプリケーションの状態
-pub type AppState = Arc<Mutex<JitCompiler>>;
+/// アプリケーションの状態。`jit`はリクエストハンドラ同士で排他制御し、`broadcaster`は
+/// `/api/stats/stream`の各接続へ`JitCompiler::execute_string`完了イベントをファンアウトする
+#[derive(Clone)]
+pub struct AppState {
+    pub jit: Arc<Mutex<JitCompiler>>,
+    pub broadcaster: Arc<StatsBroadcaster>,
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        Self {
+            jit: Arc::new(Mutex::new(JitCompiler::new())),
+            broadcaster: Arc::new(StatsBroadcaster::new()),
+        }
+    }
+}
+
+/// `/api/stats/stream`の各接続に、`JitCompiler::execute_string`完了イベントをファンアウトする
+/// ブロードキャストチャンネル。標準ライブラリの`mpsc`はマルチプロデューサ・シングル
+/// コンシューマのため、購読者（SSE接続）ごとに専用の`Sender`/`Receiver`ペアを持たせ、
+/// `publish`で全購読者へ同じイベントを送ることでブロードキャストを模している
+#[derive(Default)]
+pub struct StatsBroadcaster {
+    subscribers: Mutex<Vec<Sender<String>>>,
+}
+
+impl StatsBroadcaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 新しいSSE接続用の受信端を登録する
+    fn subscribe(&self) -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// 全購読者へイベントを配信する。送信に失敗した（＝接続が既に切れている）購読者は取り除く
+    fn publish(&self, event: String) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+/// `/api/stats/stream`で配信する1件のイベント。どの式が実行されたか・JIT化されたかと、
+/// その時点の集計統計をまとめて運ぶ
+#[derive(Serialize, Debug)]
+struct StatsStreamEvent {
+    expression: String,
+    was_jit_compiled: bool,
+    stats: ApiJitStats,
+    hot_functions: HashMap<String, u64>,
+}
+
+/// パース済みのHTTPリクエスト（ヘッダーとボディを読み切った後の表現）
+#[derive(Debug)]
+struct ParsedRequest {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+/// リクエスト処理をラップする横断的関心事のためのミドルウェアトレイト。
+/// `before`はルーティング前に呼ばれ、`Some`を返すとルーティングを短絡する。
+/// `after`はルーティング後に呼ばれ、レスポンスを書き換えられる。
+pub trait Middleware {
+    fn before(
+        &self,
+        _method: &str,
+        _path: &str,
+        _headers: &HashMap<String, String>,
+        _body: &str,
+    ) -> Option<String> {
+        None
+    }
+
+    fn after(&self, _method: &str, _path: &str, _elapsed: Duration, response: String) -> String {
+        response
+    }
+}
+
+/// アクセスログを出力するミドルウェア（メソッド・パス・ステータス・所要時間）
+pub struct AccessLogMiddleware;
+
+impl Middleware for AccessLogMiddleware {
+    fn after(&self, method: &str, path: &str, elapsed: Duration, response: String) -> String {
+        let status = response_status_code(&response);
+        println!(
+            "{} {} -> {} ({:.3}ms)",
+            method,
+            path,
+            status,
+            elapsed.as_secs_f64() * 1000.0
+        );
+        response
+    }
+}
+
+/// 指定パスへのアクセスにBearerトークンを要求するシンプルな認証ガード
+pub struct BearerAuthMiddleware {
+    pub token: String,
+    pub protected_paths: Vec<String>,
+}
+
+impl BearerAuthMiddleware {
+    pub fn new(token: impl Into<String>, protected_paths: Vec<String>) -> Self {
+        Self {
+            token: token.into(),
+            protected_paths,
+        }
+    }
+}
+
+impl Middleware for BearerAuthMiddleware {
+    fn before(
+        &self,
+        _method: &str,
+        path: &str,
+        headers: &HashMap<String, String>,
+        _body: &str,
+    ) -> Option<String> {
+        if !self.protected_paths.iter().any(|p| p == path) {
+            return None;
+        }
+
+        let expected = format!("Bearer {}", self.token);
+        match headers.get("authorization") {
+            Some(value) if value == &expected => None,
+            _ => Some(create_error_response(401, "Unauthorized")),
+        }
+    }
+}
+
+/// 所要時間を`X-Exec-Time`レスポンスヘッダーとして注入するミドルウェア
+pub struct TimingMiddleware;
+
+impl Middleware for TimingMiddleware {
+    fn after(&self, _method: &str, _path: &str, elapsed: Duration, response: String) -> String {
+        insert_response_header(&response, "X-Exec-Time", &format!("{}ns", elapsed.as_nanos()))
+    }
+}
+
+/// レスポンスの先頭行（`HTTP/1.1 200 OK`）からステータスコード部分を取り出す
+fn response_status_code(response: &str) -> &str {
+    response
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("???")
+}
+
+/// レスポンスのヘッダーブロック（空行の直前）に1行ヘッダーを挿入する
+fn insert_response_header(response: &str, name: &str, value: &str) -> String {
+    match response.find("\r\n\r\n") {
+        Some(pos) => format!(
+            "{}\r\n{}: {}{}",
+            &response[..pos],
+            name,
+            value,
+            &response[pos..]
+        ),
+        None => response.to_string(),
+    }
+}
+
+fn run_before_middlewares(
+    middlewares: &[Box<dyn Middleware + Send + Sync>],
+    method: &str,
+    path: &str,
+    headers: &HashMap<String, String>,
+    body: &str,
+) -> Option<String> {
+    middlewares
+        .iter()
+        .find_map(|m| m.before(method, path, headers, body))
+}
+
+fn run_after_middlewares(
+    middlewares: &[Box<dyn Middleware + Send + Sync>],
+    method: &str,
+    path: &str,
+    elapsed: Duration,
+    response: String,
+) -> String {
+    middlewares
+        .iter()
+        .fold(response, |resp, m| m.after(method, path, elapsed, resp))
+}
 
 /// タイムアウト付きロック獲得ヘルパー
 fn try_lock_with_timeout<T>(
@@ -80,19 +288,103 @@ fn try_lock_with_timeout<T>(
     }
 }
 
-/// HTTPサーバーを開始
-pub fn start_server(port: u16) -> std::io::Result<()> {
+/// 許可するオリジン・メソッド・ヘッダーなどを束ねるCORSポリシー設定。
+/// 既定は旧来の挙動に近い「すべてのオリジンを許可」だが、資格情報送信は既定で無効。
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub allow_credentials: bool,
+    pub max_age_seconds: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec!["*".to_string()],
+            allowed_methods: vec!["GET".to_string(), "POST".to_string(), "OPTIONS".to_string()],
+            allowed_headers: vec!["Content-Type".to_string(), "Authorization".to_string()],
+            allow_credentials: false,
+            max_age_seconds: 600,
+        }
+    }
+}
+
+/// サーバー全体の挙動を束ねる設定。個別のノブが増えるたびにここへ足していく。
+pub struct ServerOptions {
+    pub idle_timeout: Duration,
+    pub middlewares: Vec<Box<dyn Middleware + Send + Sync>>,
+    pub cors_config: CorsConfig,
+    /// `/api/stats/stream`でイベントが届かないときにkeep-aliveコメント行を送る間隔
+    pub stats_stream_interval: Duration,
+    /// `Content-Length`がこれを超えるリクエストは413で拒否する
+    pub max_body_bytes: usize,
+    /// リクエストの最初のバイトを受け取ってからヘッダー+ボディを読み切るまでの制限時間。
+    /// 超過すると408を返す（遅いクライアントがスレッドを専有し続けるのを防ぐ）
+    pub slow_request_deadline: Duration,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            middlewares: Vec::new(),
+            cors_config: CorsConfig::default(),
+            stats_stream_interval: DEFAULT_STATS_STREAM_INTERVAL,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            slow_request_deadline: DEFAULT_SLOW_REQUEST_DEADLINE,
+        }
+    }
+}
+
+/// HTTPサーバーを開始（すべて既定値: keep-aliveタイムアウト・ミドルウェアなし・CORS許可）
+pub fn start_server(port: u16) -> io::Result<()> {
+    start_server_with_options(port, ServerOptions::default())
+}
+
+/// HTTPサーバーを開始し、keep-alive接続のアイドルタイムアウトを指定する
+pub fn start_server_with_idle_timeout(port: u16, idle_timeout: Duration) -> io::Result<()> {
+    start_server_with_options(
+        port,
+        ServerOptions {
+            idle_timeout,
+            ..ServerOptions::default()
+        },
+    )
+}
+
+/// HTTPサーバーを開始し、アイドルタイムアウトと適用するミドルウェアの並び順を指定する。
+/// ミドルウェアは登録順に`before`が、逆順ではなく同じ登録順に`after`が適用される。
+pub fn start_server_with_middlewares(
+    port: u16,
+    idle_timeout: Duration,
+    middlewares: Vec<Box<dyn Middleware + Send + Sync>>,
+) -> io::Result<()> {
+    start_server_with_options(
+        port,
+        ServerOptions {
+            idle_timeout,
+            middlewares,
+            ..ServerOptions::default()
+        },
+    )
+}
+
+/// HTTPサーバーを開始し、`ServerOptions`ですべての挙動を指定する
+pub fn start_server_with_options(port: u16, options: ServerOptions) -> io::Result<()> {
     let listener = TcpListener::bind(format!("127.0.0.1:{}", port))?;
-    let jit_compiler = Arc::new(Mutex::new(JitCompiler::new()));
+    let jit_compiler = AppState::new();
+    let options = Arc::new(options);
 
     println!("🌐 HTTP Server listening on http://127.0.0.1:{}", port);
 
     for stream in listener.incoming() {
         match stream {
             Ok(stream) => {
-                let jit_clone = Arc::clone(&jit_compiler);
+                let jit_clone = jit_compiler.clone();
+                let options_clone = Arc::clone(&options);
                 thread::spawn(move || {
-                    handle_connection(stream, jit_clone);
+                    handle_connection(stream, jit_clone, options_clone);
                 });
             }
             Err(e) => {
@@ -104,55 +396,313 @@ pub fn start_server(port: u16) -> std::io::Result<()> {
     Ok(())
 }
 
-/// 接続を処理
-fn handle_connection(mut stream: TcpStream, jit_compiler: AppState) {
-    let mut buffer = [0; 4096];
+/// 1つのTCP接続を処理する。HTTP/1.1のkeep-aliveに従い、クライアントが
+/// `Connection: close` を送るか、アイドルタイムアウトに達するまで同じソケット上で
+/// 複数リクエストを捌き続ける。
+fn handle_connection(mut stream: TcpStream, jit_compiler: AppState, options: Arc<ServerOptions>) {
+    if stream.set_read_timeout(Some(options.idle_timeout)).is_err() {
+        return;
+    }
 
-    if let Ok(bytes_read) = stream.read(&mut buffer) {
-        let request = String::from_utf8_lossy(&buffer[..bytes_read]);
+    loop {
+        let request = match read_request(
+            &mut stream,
+            options.max_body_bytes,
+            options.slow_request_deadline,
+        ) {
+            Ok(Some(request)) => request,
+            Ok(None) => break, // クライアントが切断（アイドルタイムアウトも含む）
+            Err(RequestError::Malformed(e)) => {
+                let response = create_error_response(400, &format!("Malformed request: {}", e));
+                let _ = stream.write_all(response.as_bytes());
+                break;
+            }
+            Err(RequestError::SlowRequest) => {
+                let response = create_error_response(408, "Request took too long to complete");
+                let _ = stream.write_all(response.as_bytes());
+                break;
+            }
+            Err(RequestError::PayloadTooLarge) => {
+                let response = create_error_response(413, "Request body exceeds the maximum allowed size");
+                let _ = stream.write_all(response.as_bytes());
+                break;
+            }
+        };
+
+        // SSEストリームは接続を保持し続けるため、通常のレスポンス/keep-aliveの
+        // サイクルには乗せず、読み切ったらこの接続の役目は終わりとして抜ける
+        if request.method == "GET" && request.path == "/api/stats/stream" {
+            stream_stats(&mut stream, jit_compiler.clone(), options.stats_stream_interval);
+            break;
+        }
 
-        // HTTPリクエストをパース
-        let (method, path, body) = parse_http_request(&request);
+        let keep_alive = should_keep_alive(&request);
+        let start = Instant::now();
 
-        // ルーティング
-        let response = match (method.as_str(), path.as_str()) {
-            ("GET", "/api/health") => handle_health(),
-            ("GET", "/api/stats") => handle_get_stats(jit_compiler),
-            ("GET", "/api/cache") => handle_get_cache(jit_compiler),
-            ("POST", "/api/execute") => handle_execute(body, jit_compiler),
-            ("POST", "/api/reset") => handle_reset(jit_compiler),
-            ("OPTIONS", _) => handle_options(), // CORS preflight
-            _ => handle_not_found(),
+        let response = match run_before_middlewares(
+            &options.middlewares,
+            &request.method,
+            &request.path,
+            &request.headers,
+            &request.body,
+        ) {
+            Some(short_circuited) => short_circuited,
+            None => match (request.method.as_str(), request.path.as_str()) {
+                ("GET", "/api/health") => handle_health(),
+                ("GET", "/api/stats") => handle_get_stats(jit_compiler.clone()),
+                ("GET", "/api/cache") => handle_get_cache(jit_compiler.clone()),
+                ("POST", "/api/execute") => handle_execute(request.body.clone(), jit_compiler.clone()),
+                ("POST", "/api/reset") => handle_reset(jit_compiler.clone()),
+                ("OPTIONS", _) => handle_options(), // CORS preflight
+                _ => handle_not_found(),
+            },
         };
 
-        if let Err(e) = stream.write_all(response.as_bytes()) {
-            eprintln!("Failed to send response: {}", e);
+        let response = apply_cors_headers(
+            response,
+            request.headers.get("origin").map(String::as_str),
+            &options.cors_config,
+            request.method == "OPTIONS",
+        );
+
+        let response = run_after_middlewares(
+            &options.middlewares,
+            &request.method,
+            &request.path,
+            start.elapsed(),
+            response,
+        );
+
+        let accept_encoding = request.headers.get("accept-encoding").map(String::as_str);
+        let response_bytes = negotiate_compression(response, accept_encoding);
+
+        if stream.write_all(&response_bytes).is_err() {
+            break;
+        }
+
+        if !keep_alive {
+            break;
         }
     }
 }
 
-/// HTTPリクエストを簡易パース
-fn parse_http_request(request: &str) -> (String, String, String) {
-    let lines: Vec<&str> = request.split('\n').collect();
-    if lines.is_empty() {
-        return ("GET".to_string(), "/".to_string(), "".to_string());
+/// `GET /api/stats/stream`を処理する。ヘッダーと接続時点のスナップショットを送ったあと、
+/// `StatsBroadcaster`経由で`JitCompiler::execute_string`完了イベントが届くたびにSSEとして
+/// 転送する。`keep_alive_interval`の間イベントが届かなければ、アイドルタイムアウトで
+/// 切断されないようコメント行を送る。
+fn stream_stats(stream: &mut TcpStream, jit_compiler: AppState, keep_alive_interval: Duration) {
+    let header = "HTTP/1.1 200 OK\r\n\
+                  Content-Type: text/event-stream\r\n\
+                  Cache-Control: no-cache\r\n\
+                  Connection: keep-alive\r\n\r\n";
+    if stream.write_all(header.as_bytes()).is_err() {
+        return;
     }
 
-    // リクエストライン（例: "GET /api/health HTTP/1.1"）
-    let request_line_parts: Vec<&str> = lines[0].split_whitespace().collect();
-    let method = request_line_parts.get(0).unwrap_or(&"GET").to_string();
-    let path = request_line_parts.get(1).unwrap_or(&"/").to_string();
+    // 接続直後は次のイベントまでダッシュボードが空の状態で待たされないよう、
+    // 現在の状態をスナップショットとして先に1件送っておく
+    if let Ok(jit) = try_lock_with_timeout(&jit_compiler.jit, Duration::from_secs(5)) {
+        let snapshot = build_stats_event(&StatsStreamEvent {
+            expression: String::new(),
+            was_jit_compiled: false,
+            stats: build_api_jit_stats(&jit),
+            hot_functions: jit.get_stats().hot_functions.clone(),
+        });
+        drop(jit);
+        if stream.write_all(snapshot.as_bytes()).is_err() {
+            return;
+        }
+    }
 
-    // ボディを抽出（簡易実装）
-    let body = if let Some(body_start) = request.find("\r\n\r\n") {
-        request[body_start + 4..].to_string()
-    } else if let Some(body_start) = request.find("\n\n") {
-        request[body_start + 2..].to_string()
-    } else {
-        "".to_string()
+    let receiver = jit_compiler.broadcaster.subscribe();
+
+    loop {
+        match receiver.recv_timeout(keep_alive_interval) {
+            Ok(event) => {
+                if stream.write_all(event.as_bytes()).is_err() || stream.flush().is_err() {
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if stream.write_all(b": keep-alive\n\n").is_err() || stream.flush().is_err() {
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// JIT実行イベントを1件の`data: {json}\n\n`形式のSSEイベントとして組み立てる
+fn build_stats_event(event: &StatsStreamEvent) -> String {
+    let json = serde_json::to_string(event).unwrap_or_else(|_| "{}".to_string());
+    format!("data: {}\n\n", json)
+}
+
+/// `JitCompiler`の現在の統計とキャッシュ情報から`ApiJitStats`を組み立てる
+fn build_api_jit_stats(jit: &JitCompiler) -> ApiJitStats {
+    let stats = jit.get_stats();
+    let cache_info = jit.get_jit_cache_info();
+
+    ApiJitStats {
+        total_executions: stats.total_executions,
+        jit_compilations: stats.jit_compilations,
+        total_execution_time_ns: stats.total_execution_time_ns,
+        total_compilation_time_ns: stats.total_compilation_time_ns,
+        average_execution_time_ns: if stats.total_executions > 0 {
+            stats.total_execution_time_ns / stats.total_executions
+        } else {
+            0
+        },
+        average_compilation_time_ns: if stats.jit_compilations > 0 {
+            stats.total_compilation_time_ns / stats.jit_compilations
+        } else {
+            0
+        },
+        cache_entries: cache_info.len(),
+    }
+}
+
+/// `Connection`ヘッダーに基づき、接続を維持すべきか判定する。
+/// HTTP/1.1は明示的な`close`がない限り既定で持続接続とみなす。
+fn should_keep_alive(request: &ParsedRequest) -> bool {
+    !matches!(
+        request.headers.get("connection").map(|v| v.to_lowercase()),
+        Some(ref v) if v == "close"
+    )
+}
+
+/// `read_request`が失敗しうる理由。それぞれ異なるHTTPステータスに対応する
+/// （`Malformed` -> 400, `SlowRequest` -> 408, `PayloadTooLarge` -> 413）。
+enum RequestError {
+    Malformed(String),
+    SlowRequest,
+    PayloadTooLarge,
+}
+
+/// ソケットからヘッダー全体（`\r\n\r\n`まで）を読み切り、`Content-Length`に従って
+/// ボディを読み切ってからリクエストを組み立てる。`Ok(None)`はクリーンなEOF（接続終了）。
+/// `max_body_bytes`を超える`Content-Length`は即座に拒否し、最初のバイトを受け取ってから
+/// `slow_request_deadline`を過ぎてもヘッダー+ボディを読み切れなければ408として扱う。
+fn read_request(
+    stream: &mut TcpStream,
+    max_body_bytes: usize,
+    slow_request_deadline: Duration,
+) -> Result<Option<ParsedRequest>, RequestError> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let mut started_at: Option<Instant> = None;
+
+    let check_deadline = |started_at: &Option<Instant>| -> Result<(), RequestError> {
+        match started_at {
+            Some(t) if t.elapsed() > slow_request_deadline => Err(RequestError::SlowRequest),
+            _ => Ok(()),
+        }
+    };
+
+    let header_end = loop {
+        if let Some(pos) = find_header_end(&buffer) {
+            break pos;
+        }
+
+        match stream.read(&mut chunk) {
+            Ok(0) => {
+                if buffer.is_empty() {
+                    return Ok(None);
+                }
+                return Err(RequestError::Malformed(
+                    "connection closed before headers were complete".to_string(),
+                ));
+            }
+            Ok(n) => {
+                started_at.get_or_insert_with(Instant::now);
+                buffer.extend_from_slice(&chunk[..n]);
+                check_deadline(&started_at)?;
+            }
+            Err(e) if is_timeout(&e) => {
+                if buffer.is_empty() {
+                    return Ok(None);
+                }
+                return Err(RequestError::SlowRequest);
+            }
+            Err(e) => return Err(RequestError::Malformed(e.to_string())),
+        }
     };
 
-    (method, path, body)
+    let header_text = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let (method, path, headers) =
+        parse_request_head(&header_text).map_err(RequestError::Malformed)?;
+    let content_length = headers
+        .get("content-length")
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    if content_length > max_body_bytes {
+        return Err(RequestError::PayloadTooLarge);
+    }
+
+    while buffer.len() < header_end + content_length {
+        match stream.read(&mut chunk) {
+            Ok(0) => {
+                return Err(RequestError::Malformed(
+                    "connection closed before body was complete".to_string(),
+                ))
+            }
+            Ok(n) => {
+                started_at.get_or_insert_with(Instant::now);
+                buffer.extend_from_slice(&chunk[..n]);
+                check_deadline(&started_at)?;
+            }
+            Err(e) if is_timeout(&e) => return Err(RequestError::SlowRequest),
+            Err(e) => return Err(RequestError::Malformed(e.to_string())),
+        }
+    }
+
+    let body =
+        String::from_utf8_lossy(&buffer[header_end..header_end + content_length]).to_string();
+
+    Ok(Some(ParsedRequest {
+        method,
+        path,
+        headers,
+        body,
+    }))
+}
+
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut)
+}
+
+/// バッファ中にヘッダー終端の空行（`\r\n\r\n`）を探し、見つかればボディ開始位置を返す
+fn find_header_end(buffer: &[u8]) -> Option<usize> {
+    buffer
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|pos| pos + 4)
+}
+
+/// ヘッダーブロックの生テキストからリクエストラインとヘッダーを取り出す
+fn parse_request_head(
+    header_text: &str,
+) -> Result<(String, String, HashMap<String, String>), String> {
+    let mut lines = header_text.split("\r\n");
+
+    let request_line = lines.next().filter(|l| !l.is_empty()).ok_or("empty request")?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or("missing HTTP method")?.to_string();
+    let path = parts.next().ok_or("missing request path")?.to_string();
+
+    let mut headers = HashMap::new();
+    for line in lines {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    Ok((method, path, headers))
 }
 
 /// ヘルスチェック
@@ -168,41 +718,18 @@ fn handle_health() -> String {
 
 /// 統計情報を取得
 fn handle_get_stats(jit_compiler: AppState) -> String {
-    match try_lock_with_timeout(&jit_compiler, Duration::from_secs(5)) {
-        Ok(jit) => {
-            let stats = jit.get_stats();
-            let cache_info = jit.get_jit_cache_info();
-
-            let api_stats = ApiJitStats {
-                total_executions: stats.total_executions,
-                jit_compilations: stats.jit_compilations,
-                total_execution_time_ns: stats.total_execution_time_ns,
-                total_compilation_time_ns: stats.total_compilation_time_ns,
-                average_execution_time_ns: if stats.total_executions > 0 {
-                    stats.total_execution_time_ns / stats.total_executions
-                } else {
-                    0
-                },
-                average_compilation_time_ns: if stats.jit_compilations > 0 {
-                    stats.total_compilation_time_ns / stats.jit_compilations
-                } else {
-                    0
-                },
-                cache_entries: cache_info.len(),
-            };
-
-            match serde_json::to_string(&api_stats) {
-                Ok(json) => create_http_response(200, "OK", &json),
-                Err(_) => create_error_response(500, "JSON serialization failed"),
-            }
-        }
+    match try_lock_with_timeout(&jit_compiler.jit, Duration::from_secs(5)) {
+        Ok(jit) => match serde_json::to_string(&build_api_jit_stats(&jit)) {
+            Ok(json) => create_http_response(200, "OK", &json),
+            Err(_) => create_error_response(500, "JSON serialization failed"),
+        },
         Err(msg) => create_error_response(503, &msg),
     }
 }
 
 /// キャッシュ情報を取得
 fn handle_get_cache(jit_compiler: AppState) -> String {
-    match try_lock_with_timeout(&jit_compiler, Duration::from_secs(5)) {
+    match try_lock_with_timeout(&jit_compiler.jit, Duration::from_secs(5)) {
         Ok(jit) => {
             let cache_info = jit.get_jit_cache_info();
             let entries: Vec<CacheEntry> = cache_info
@@ -237,10 +764,18 @@ fn handle_execute(body: String, jit_compiler: AppState) -> String {
         Err(_) => return create_error_response(400, "Invalid JSON"),
     };
 
-    match try_lock_with_timeout(&jit_compiler, Duration::from_secs(10)) {
+    match try_lock_with_timeout(&jit_compiler.jit, Duration::from_secs(10)) {
         Ok(mut jit) => {
             match jit.execute_string(&request.code) {
                 Ok(result) => {
+                    let event = StatsStreamEvent {
+                        expression: request.code.clone(),
+                        was_jit_compiled: result.was_jit_compiled,
+                        stats: build_api_jit_stats(&jit),
+                        hot_functions: jit.get_stats().hot_functions.clone(),
+                    };
+                    jit_compiler.broadcaster.publish(build_stats_event(&event));
+
                     let response = ExecuteResponse {
                         result: result.value,
                         execution_time_ns: result.execution_time_ns,
@@ -266,7 +801,7 @@ fn handle_execute(body: String, jit_compiler: AppState) -> String {
 
 /// 統計をリセット
 fn handle_reset(jit_compiler: AppState) -> String {
-    match try_lock_with_timeout(&jit_compiler, Duration::from_secs(5)) {
+    match try_lock_with_timeout(&jit_compiler.jit, Duration::from_secs(5)) {
         Ok(mut jit) => {
             jit.reset_stats();
 
@@ -281,14 +816,64 @@ fn handle_reset(jit_compiler: AppState) -> String {
     }
 }
 
-/// CORS プリフライトリクエストを処理
+/// CORS プリフライトリクエストを処理（実際のCORSヘッダーは`apply_cors_headers`が付与する）
 fn handle_options() -> String {
-    let response = "HTTP/1.1 200 OK\r\n\
-                   Access-Control-Allow-Origin: *\r\n\
-                   Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
-                   Access-Control-Allow-Headers: Content-Type\r\n\
-                   Content-Length: 0\r\n\r\n";
-    response.to_string()
+    "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string()
+}
+
+/// `CorsConfig`のアローリストと、リクエストの`Origin`ヘッダーを突き合わせ、
+/// エコーバックすべきオリジンを1つだけ決定する（`*`はそのまま返さない）。
+fn resolve_allowed_origin(config: &CorsConfig, origin: Option<&str>) -> Option<String> {
+    let origin = origin?;
+    if config
+        .allowed_origins
+        .iter()
+        .any(|allowed| allowed == "*" || allowed == origin)
+    {
+        Some(origin.to_string())
+    } else {
+        None
+    }
+}
+
+/// レスポンスにCORSヘッダーを付与する。アローリストに一致しない`Origin`の場合は何も付けない。
+/// プリフライト（`OPTIONS`）の場合のみ`Allow-Methods`/`Allow-Headers`/`Max-Age`も付与する。
+fn apply_cors_headers(
+    response: String,
+    origin: Option<&str>,
+    config: &CorsConfig,
+    is_preflight: bool,
+) -> String {
+    let allowed_origin = match resolve_allowed_origin(config, origin) {
+        Some(allowed_origin) => allowed_origin,
+        None => return response,
+    };
+
+    let mut response = insert_response_header(&response, "Access-Control-Allow-Origin", &allowed_origin);
+
+    if config.allow_credentials {
+        response = insert_response_header(&response, "Access-Control-Allow-Credentials", "true");
+    }
+
+    if is_preflight {
+        response = insert_response_header(
+            &response,
+            "Access-Control-Allow-Methods",
+            &config.allowed_methods.join(", "),
+        );
+        response = insert_response_header(
+            &response,
+            "Access-Control-Allow-Headers",
+            &config.allowed_headers.join(", "),
+        );
+        response = insert_response_header(
+            &response,
+            "Access-Control-Max-Age",
+            &config.max_age_seconds.to_string(),
+        );
+    }
+
+    response
 }
 
 /// 404を処理
@@ -301,7 +886,6 @@ fn create_http_response(status_code: u16, status_text: &str, body: &str) -> Stri
     format!(
         "HTTP/1.1 {} {}\r\n\
          Content-Type: application/json\r\n\
-         Access-Control-Allow-Origin: *\r\n\
          Content-Length: {}\r\n\r\n{}",
         status_code,
         status_text,
@@ -310,6 +894,93 @@ fn create_http_response(status_code: u16, status_text: &str, body: &str) -> Stri
     )
 }
 
+/// クライアントの`Accept-Encoding`に基づき、組み立て済みレスポンスの本文を圧縮する。
+/// `br`を優先し、次に`gzip`、どちらも提示されなければ無圧縮のまま返す。
+/// `COMPRESSION_MIN_BYTES`未満の本文は圧縮のオーバーヘッドの方が大きいため対象外。
+fn negotiate_compression(response: String, accept_encoding: Option<&str>) -> Vec<u8> {
+    let (head, body) = match response.split_once("\r\n\r\n") {
+        Some(parts) => parts,
+        None => return response.into_bytes(),
+    };
+
+    if body.len() < COMPRESSION_MIN_BYTES {
+        return response.into_bytes();
+    }
+
+    let accept_encoding = accept_encoding.unwrap_or("").to_lowercase();
+    let encoding = if accept_encoding.contains("br") {
+        Some(ContentEncoding::Brotli)
+    } else if accept_encoding.contains("gzip") {
+        Some(ContentEncoding::Gzip)
+    } else {
+        None
+    };
+
+    let encoding = match encoding {
+        Some(encoding) => encoding,
+        None => return response.into_bytes(),
+    };
+
+    let compressed = match encoding {
+        ContentEncoding::Gzip => compress_gzip(body.as_bytes()),
+        ContentEncoding::Brotli => compress_brotli(body.as_bytes()),
+    };
+
+    let head = replace_content_length(head, compressed.len());
+    let mut out = format!(
+        "{}\r\nContent-Encoding: {}\r\n\r\n",
+        head,
+        encoding.as_str()
+    )
+    .into_bytes();
+    out.extend_from_slice(&compressed);
+    out
+}
+
+/// `create_http_response`が出す`Content-Encoding`候補
+enum ContentEncoding {
+    Gzip,
+    Brotli,
+}
+
+impl ContentEncoding {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Brotli => "br",
+        }
+    }
+}
+
+/// ヘッダーブロック中の`Content-Length`行を新しい値に置き換える
+fn replace_content_length(head: &str, new_len: usize) -> String {
+    head.split("\r\n")
+        .map(|line| {
+            if line.to_lowercase().starts_with("content-length:") {
+                format!("Content-Length: {}", new_len)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+fn compress_gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).expect("gzip compression failed");
+    encoder.finish().expect("gzip compression failed")
+}
+
+fn compress_brotli(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    {
+        let mut writer = CompressorWriter::new(&mut out, 4096, 5, 22);
+        writer.write_all(data).expect("brotli compression failed");
+    }
+    out
+}
+
 /// エラーレスポンスを作成
 fn create_error_response(status_code: u16, message: &str) -> String {
     let error_response = ErrorResponse {
@@ -323,7 +994,10 @@ fn create_error_response(status_code: u16, message: &str) -> String {
 
     let status_text = match status_code {
         400 => "Bad Request",
+        401 => "Unauthorized",
         404 => "Not Found",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
         500 => "Internal Server Error",
         503 => "Service Unavailable",
         _ => "Error",
@@ -337,23 +1011,32 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_http_request() {
-        let request = "GET /api/health HTTP/1.1\r\nHost: localhost:3001\r\n\r\n";
-        let (method, path, body) = parse_http_request(request);
+    fn test_parse_request_head() {
+        let (method, path, headers) =
+            parse_request_head("GET /api/health HTTP/1.1\r\nHost: localhost:3001\r\n").unwrap();
 
         assert_eq!(method, "GET");
         assert_eq!(path, "/api/health");
-        assert_eq!(body, "");
+        assert_eq!(headers.get("host"), Some(&"localhost:3001".to_string()));
     }
 
     #[test]
-    fn test_parse_post_request() {
-        let request = "POST /api/execute HTTP/1.1\r\nContent-Type: application/json\r\n\r\n{\"code\":\"1+2\"}";
-        let (method, path, body) = parse_http_request(request);
+    fn test_parse_request_head_with_content_length() {
+        let (method, path, headers) = parse_request_head(
+            "POST /api/execute HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: 14\r\n",
+        )
+        .unwrap();
 
         assert_eq!(method, "POST");
         assert_eq!(path, "/api/execute");
-        assert_eq!(body, r#"{"code":"1+2"}"#);
+        assert_eq!(headers.get("content-length"), Some(&"14".to_string()));
+    }
+
+    #[test]
+    fn test_find_header_end() {
+        let buffer = b"GET / HTTP/1.1\r\nHost: x\r\n\r\nbody-bytes";
+        let pos = find_header_end(buffer).unwrap();
+        assert_eq!(&buffer[pos..], b"body-bytes");
     }
 
     #[test]
@@ -362,4 +1045,272 @@ mod tests {
         assert!(response.contains("200 OK"));
         assert!(response.contains("healthy"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_should_keep_alive_defaults_true() {
+        let request = ParsedRequest {
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            headers: HashMap::new(),
+            body: String::new(),
+        };
+        assert!(should_keep_alive(&request));
+    }
+
+    #[test]
+    fn test_should_keep_alive_honors_connection_close() {
+        let mut headers = HashMap::new();
+        headers.insert("connection".to_string(), "close".to_string());
+        let request = ParsedRequest {
+            method: "GET".to_string(),
+            path: "/".to_string(),
+            headers,
+            body: String::new(),
+        };
+        assert!(!should_keep_alive(&request));
+    }
+
+    #[test]
+    fn test_insert_response_header() {
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+        let updated = insert_response_header(response, "X-Exec-Time", "123ns");
+        assert!(updated.contains("X-Exec-Time: 123ns\r\n\r\nok"));
+    }
+
+    #[test]
+    fn test_response_status_code() {
+        assert_eq!(response_status_code("HTTP/1.1 404 Not Found\r\n\r\n"), "404");
+    }
+
+    #[test]
+    fn test_timing_middleware_injects_header() {
+        let middleware = TimingMiddleware;
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_string();
+        let updated = middleware.after("GET", "/api/health", Duration::from_millis(5), response);
+        assert!(updated.contains("X-Exec-Time:"));
+    }
+
+    #[test]
+    fn test_access_log_middleware_passes_response_through_unchanged() {
+        let middleware = AccessLogMiddleware;
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_string();
+        let updated =
+            middleware.after("GET", "/api/health", Duration::from_millis(5), response.clone());
+        assert_eq!(updated, response);
+    }
+
+    #[test]
+    fn test_run_before_middlewares_short_circuits_on_first_some() {
+        let middlewares: Vec<Box<dyn Middleware + Send + Sync>> = vec![
+            Box::new(AccessLogMiddleware),
+            Box::new(BearerAuthMiddleware::new("secret", vec!["/api/execute".to_string()])),
+        ];
+        let response =
+            run_before_middlewares(&middlewares, "POST", "/api/execute", &HashMap::new(), "{}");
+        assert!(response.unwrap().contains("401"));
+    }
+
+    #[test]
+    fn test_run_before_middlewares_returns_none_when_all_pass() {
+        let middlewares: Vec<Box<dyn Middleware + Send + Sync>> =
+            vec![Box::new(AccessLogMiddleware), Box::new(TimingMiddleware)];
+        let response =
+            run_before_middlewares(&middlewares, "GET", "/api/health", &HashMap::new(), "");
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn test_run_after_middlewares_applies_in_registration_order() {
+        // `AccessLogMiddleware`はレスポンスを変更しないので、`TimingMiddleware`が
+        // ヘッダーを注入した後の文字列がそのまま最終結果になるはず
+        let middlewares: Vec<Box<dyn Middleware + Send + Sync>> =
+            vec![Box::new(TimingMiddleware), Box::new(AccessLogMiddleware)];
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_string();
+        let updated = run_after_middlewares(
+            &middlewares,
+            "GET",
+            "/api/health",
+            Duration::from_millis(5),
+            response,
+        );
+        assert!(updated.contains("X-Exec-Time:"));
+    }
+
+    #[test]
+    fn test_start_server_with_middlewares_populates_server_options() {
+        // `start_server_with_middlewares`は実際にリッスンしてしまうため直接は呼べないが、
+        // 組み立てる`ServerOptions`が既定値の空の`middlewares`のままにならないことは、
+        // 同じ組み立てロジック（`..ServerOptions::default()`）を経由して検証できる
+        let middlewares: Vec<Box<dyn Middleware + Send + Sync>> = vec![Box::new(TimingMiddleware)];
+        let options = ServerOptions {
+            idle_timeout: Duration::from_secs(1),
+            middlewares,
+            ..ServerOptions::default()
+        };
+        assert_eq!(options.middlewares.len(), 1);
+    }
+
+    #[test]
+    fn test_bearer_auth_middleware_blocks_without_token() {
+        let middleware =
+            BearerAuthMiddleware::new("secret", vec!["/api/execute".to_string()]);
+        let response = middleware.before("POST", "/api/execute", &HashMap::new(), "{}");
+        assert!(response.is_some());
+        assert!(response.unwrap().contains("401"));
+    }
+
+    #[test]
+    fn test_bearer_auth_middleware_allows_with_token() {
+        let middleware =
+            BearerAuthMiddleware::new("secret", vec!["/api/execute".to_string()]);
+        let mut headers = HashMap::new();
+        headers.insert("authorization".to_string(), "Bearer secret".to_string());
+        let response = middleware.before("POST", "/api/execute", &headers, "{}");
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn test_bearer_auth_middleware_ignores_unprotected_paths() {
+        let middleware =
+            BearerAuthMiddleware::new("secret", vec!["/api/execute".to_string()]);
+        let response = middleware.before("GET", "/api/health", &HashMap::new(), "");
+        assert!(response.is_none());
+    }
+
+    #[test]
+    fn test_negotiate_compression_skips_small_bodies() {
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_string();
+        let bytes = negotiate_compression(response.clone(), Some("gzip"));
+        assert_eq!(bytes, response.into_bytes());
+    }
+
+    #[test]
+    fn test_negotiate_compression_skips_without_matching_encoding() {
+        let body = "x".repeat(300);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let bytes = negotiate_compression(response.clone(), Some("identity"));
+        assert_eq!(bytes, response.into_bytes());
+    }
+
+    #[test]
+    fn test_negotiate_compression_prefers_brotli_and_updates_headers() {
+        let body = "x".repeat(300);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let bytes = negotiate_compression(response, Some("gzip, br"));
+        let head_end = bytes.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8_lossy(&bytes[..head_end]);
+        assert!(head.contains("Content-Encoding: br"));
+        assert!(bytes.len() < head_end + 4 + body.len());
+    }
+
+    #[test]
+    fn test_replace_content_length() {
+        let head = "HTTP/1.1 200 OK\r\nContent-Length: 100\r\nContent-Type: application/json";
+        let updated = replace_content_length(head, 42);
+        assert!(updated.contains("Content-Length: 42"));
+        assert!(!updated.contains("Content-Length: 100"));
+    }
+
+    #[test]
+    fn test_resolve_allowed_origin_matches_allowlist() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://example.com".to_string()],
+            ..CorsConfig::default()
+        };
+        assert_eq!(
+            resolve_allowed_origin(&config, Some("https://example.com")),
+            Some("https://example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_allowed_origin_rejects_unlisted_origin() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://example.com".to_string()],
+            ..CorsConfig::default()
+        };
+        assert_eq!(resolve_allowed_origin(&config, Some("https://evil.test")), None);
+    }
+
+    #[test]
+    fn test_apply_cors_headers_omits_header_without_origin() {
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".to_string();
+        let updated = apply_cors_headers(response, None, &CorsConfig::default(), false);
+        assert!(!updated.contains("Access-Control-Allow-Origin"));
+    }
+
+    #[test]
+    fn test_apply_cors_headers_adds_preflight_headers() {
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string();
+        let updated = apply_cors_headers(
+            response,
+            Some("https://example.com"),
+            &CorsConfig::default(),
+            true,
+        );
+        assert!(updated.contains("Access-Control-Allow-Origin: https://example.com"));
+        assert!(updated.contains("Access-Control-Allow-Methods"));
+        assert!(updated.contains("Access-Control-Max-Age"));
+    }
+
+    #[test]
+    fn test_build_stats_event_is_a_valid_sse_data_line() {
+        let jit = JitCompiler::new();
+        let event = build_stats_event(&StatsStreamEvent {
+            expression: "1 + 1".to_string(),
+            was_jit_compiled: false,
+            stats: build_api_jit_stats(&jit),
+            hot_functions: jit.get_stats().hot_functions.clone(),
+        });
+        assert!(event.starts_with("data: "));
+        assert!(event.ends_with("\n\n"));
+        assert!(event.contains("total_executions"));
+        assert!(event.contains("1 + 1"));
+    }
+
+    #[test]
+    fn test_stats_broadcaster_fans_out_to_all_subscribers() {
+        let broadcaster = StatsBroadcaster::new();
+        let rx1 = broadcaster.subscribe();
+        let rx2 = broadcaster.subscribe();
+
+        broadcaster.publish("data: {}\n\n".to_string());
+
+        assert_eq!(rx1.recv().unwrap(), "data: {}\n\n");
+        assert_eq!(rx2.recv().unwrap(), "data: {}\n\n");
+    }
+
+    #[test]
+    fn test_stats_broadcaster_drops_disconnected_subscribers() {
+        let broadcaster = StatsBroadcaster::new();
+        {
+            let _rx = broadcaster.subscribe(); // 即座にドロップされる購読者
+        }
+        broadcaster.publish("data: {}\n\n".to_string());
+        assert_eq!(broadcaster.subscribers.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_create_error_response_maps_new_status_codes() {
+        let timeout = create_error_response(408, "too slow");
+        assert!(timeout.contains("408 Request Timeout"));
+
+        let too_large = create_error_response(413, "too big");
+        assert!(too_large.contains("413 Payload Too Large"));
+    }
+
+    #[test]
+    fn test_server_options_defaults_are_sane() {
+        let options = ServerOptions::default();
+        assert_eq!(options.max_body_bytes, 1024 * 1024);
+        assert_eq!(options.slow_request_deadline, Duration::from_secs(5));
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
 /// 数式言語のAST（抽象構文木）定義
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -7,6 +8,15 @@ pub enum Expr {
     /// 整数リテラル: 42
     Number(i64),
 
+    /// 浮動小数点リテラル: 3.14
+    Float(f64),
+
+    /// 真偽値リテラル: true / false
+    Bool(bool),
+
+    /// 文字列リテラル: "hello"
+    Str(String),
+
     /// 変数参照: x
     Variable(String),
 
@@ -35,6 +45,50 @@ pub enum Expr {
         true_expr: Box<Expr>,
         false_expr: Box<Expr>,
     },
+
+    /// 短絡評価される論理演算: left && right, left || right
+    Logical {
+        left: Box<Expr>,
+        op: LogicalOp,
+        right: Box<Expr>,
+    },
+
+    /// 単項演算: op operand
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+    },
+
+    /// while文: while(condition, body)。conditionが非0の間bodyを繰り返し評価し、
+    /// 最後に評価されたbodyの値を返す（一度も実行されなければ0）
+    While {
+        condition: Box<Expr>,
+        body: Box<Expr>,
+    },
+
+    /// `;` 区切りの式の列。各式を順番に評価し、最後の式の値を返す
+    Sequence(Vec<Expr>),
+
+    /// 未束縛変数に対するフォールバック: `x ?? 5` / `default(x, 5)`。
+    /// `primary`が未束縛の`Variable`として評価に失敗した場合に限り`fallback`を評価して返す
+    /// （`fallback`は遅延評価され、`primary`が束縛済みなら一切評価されない）
+    Fallback {
+        primary: Box<Expr>,
+        fallback: Box<Expr>,
+    },
+}
+
+/// 短絡評価される論理演算子
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LogicalOp {
+    And, // &&
+    Or,  // ||
+}
+
+/// 単項演算子
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UnaryOp {
+    Not, // !
 }
 
 /// 二項演算子
@@ -53,10 +107,73 @@ pub enum BinaryOp {
     GreaterEq,// >=
 }
 
+/// 実行時の値。インタープリタが直接返せる型を整数だけに限らず、浮動小数点・真偽値まで
+/// 一つの型で表現する。JITコード生成（`jit::codegen`）は依然として整数レジスタのみを
+/// 扱うマシンなので、`Value`を生のままでは流し込めない。`Interpreter`の環境と
+/// `JitCompiler`/`ExecutableMemory`のシード値の境界だけで`as_i64_lossy`による
+/// 変換が必要になる（詳細は`jit::JitCompiler::execute`を参照）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Value {
+    /// 真偽値としての評価（`If`/`While`/`Logical`/`Unary::Not`の条件判定に使う）。
+    /// `Bool`はそのまま、数値はC言語的に0以外を真とみなす
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Int(n) => *n != 0,
+            Value::Float(f) => *f != 0.0,
+            Value::Bool(b) => *b,
+        }
+    }
+
+    /// `f64`としての値。算術演算でFloatへ昇格する際の共通の取り出し口
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Value::Int(n) => *n as f64,
+            Value::Float(f) => *f,
+            Value::Bool(b) => if *b { 1.0 } else { 0.0 },
+        }
+    }
+
+    /// `i64`への非可逆変換（小数部は切り捨て）。JITコード生成は整数レジスタしか
+    /// 扱えないため、`Interpreter`環境と`JitCompiler`のシード値の境界でのみ使う
+    pub fn as_i64_lossy(&self) -> i64 {
+        match self {
+            Value::Int(n) => *n,
+            Value::Float(f) => *f as i64,
+            Value::Bool(b) => if *b { 1 } else { 0 },
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// `assert_eq!(result.value, 42)`のように、整数リテラルとの比較をそのまま書けるように
+/// するための補助実装。`Value::Int`以外とは等しくならない（`Float`/`Bool`を暗黙に
+/// 数値化してまで一致させることはしない）
+impl PartialEq<i64> for Value {
+    fn eq(&self, other: &i64) -> bool {
+        matches!(self, Value::Int(n) if n == other)
+    }
+}
+
 /// 実行環境（変数の値を保持）
 #[derive(Debug, Clone, Default)]
 pub struct Environment {
-    pub variables: HashMap<String, i64>,
+    pub variables: HashMap<String, Value>,
 }
 
 impl Environment {
@@ -66,11 +183,11 @@ impl Environment {
         }
     }
 
-    pub fn set(&mut self, name: String, value: i64) {
+    pub fn set(&mut self, name: String, value: Value) {
         self.variables.insert(name, value);
     }
 
-    pub fn get(&self, name: &str) -> Option<i64> {
+    pub fn get(&self, name: &str) -> Option<Value> {
         self.variables.get(name).copied()
     }
 }
@@ -78,13 +195,25 @@ impl Environment {
 /// 実行結果
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExecutionResult {
-    pub value: i64,
-    pub environment: HashMap<String, i64>,
+    pub value: Value,
+    pub environment: HashMap<String, Value>,
     pub execution_time_ns: u64,
     pub compilation_time_ns: Option<u64>,
     pub was_jit_compiled: bool,
 }
 
+/// 式のハッシュ値を計算する（JITキャッシュのキーやホットループ検出に使う共通ロジック）。
+/// `Expr`は`Hash`を導出していないため、デバッグ表示文字列をハッシュする簡易実装
+pub fn hash_expr(expr: &Expr) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let expr_str = format!("{:?}", expr);
+    let mut hasher = DefaultHasher::new();
+    expr_str.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// JIT統計情報
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JitStats {
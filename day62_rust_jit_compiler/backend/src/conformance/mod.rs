@@ -0,0 +1,416 @@
+// ファイル駆動のコンフォーマンステストハーネス
+//
+// `tests/cases/`配下のプレーンテキストの固定ケースを読み込み、各ケースを
+// `Interpreter`と`JitCompiler::execute_string`の両方で実行して期待値と突き合わせる。
+// 個々のケースの失敗（あるいはパニック）が全体の実行を止めないようにcatch_unwindで
+// 捕まえ、`test_ignore.txt`のスキップリストを反映したうえで、最後にpass/fail/ignored/
+// panickedの集計とケースごとの差分レポートを出力する。外部の言語仕様スイートを
+// CIで走らせる実行系の縮小版。`test_lexer_parser_interpreter`/`test_jit_compiler`の
+// 固定assert_eq!群を置き換え、このディレクトリへケースを追加するだけで
+// リグレッションコーパスを育てられるようにする
+
+use crate::ast::Value;
+use crate::interpreter::Interpreter;
+use crate::jit::JitCompiler;
+use crate::parser::Parser;
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+/// 1件のケースが期待する実行結果
+#[derive(Debug, Clone, PartialEq)]
+enum Expected {
+    Value(Value),
+    Error,
+}
+
+/// `tests/cases/`の1ファイルに対応するケース
+#[derive(Debug, Clone)]
+struct ConformanceCase {
+    name: String,
+    expr_source: String,
+    env: HashMap<String, Value>,
+    expected: Expected,
+}
+
+/// ケース1件を両エンジンで実行した結果
+#[derive(Debug)]
+enum CaseOutcome {
+    Passed,
+    Ignored { reason: String },
+    Failed { details: String },
+    Panicked { details: String },
+}
+
+/// 全ケース実行後の集計とケースごとの結果
+pub struct ConformanceReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    pub panicked: usize,
+    details: Vec<(String, CaseOutcome)>,
+}
+
+impl ConformanceReport {
+    /// 無視されていないケースに1件でも失敗・パニックがあれば`false`
+    pub fn is_success(&self) -> bool {
+        self.failed == 0 && self.panicked == 0
+    }
+
+    /// サマリーテーブルとケースごとの差分を標準出力へ表示する
+    pub fn print(&self) {
+        println!("\n📋 コンフォーマンステスト結果");
+        println!("{}", "=".repeat(50));
+        println!("  ✅ passed:   {}", self.passed);
+        println!("  ❌ failed:   {}", self.failed);
+        println!("  🙈 ignored:  {}", self.ignored);
+        println!("  💥 panicked: {}", self.panicked);
+        println!("{}", "=".repeat(50));
+
+        for (name, outcome) in &self.details {
+            match outcome {
+                CaseOutcome::Passed => {}
+                CaseOutcome::Ignored { reason } => println!("  🙈 {} (ignored: {})", name, reason),
+                CaseOutcome::Failed { details } => println!("  ❌ {}\n     {}", name, details),
+                CaseOutcome::Panicked { details } => println!("  💥 {}\n     {}", name, details),
+            }
+        }
+    }
+}
+
+/// `test_ignore.txt`の1行: グロブパターンとスキップ理由
+struct IgnoreRule {
+    pattern: String,
+    reason: String,
+}
+
+/// `tests/cases/`配下の全ケースを実行し、レポートを返す
+pub fn run(cases_dir: &Path, ignore_file: &Path) -> Result<ConformanceReport> {
+    let ignore_rules = load_ignore_rules(ignore_file)?;
+
+    let mut entries: Vec<_> = fs::read_dir(cases_dir)
+        .with_context(|| format!("failed to read conformance cases directory: {}", cases_dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "txt").unwrap_or(false))
+        .collect();
+    entries.sort();
+
+    // ケースがパニックした際にデフォルトのパニックフックが標準エラーへ垂れ流す
+    // バックトレース表示で出力が読みにくくならないよう、実行中だけ黙らせておく
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut ignored = 0;
+    let mut panicked = 0;
+    let mut details = Vec::new();
+
+    for path in entries {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("<unknown>")
+            .to_string();
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read conformance case: {}", path.display()))?;
+        let case = parse_case(name, &content)
+            .with_context(|| format!("failed to parse conformance case: {}", path.display()))?;
+
+        let outcome = if let Some(reason) = find_ignore_reason(&ignore_rules, &case.name) {
+            ignored += 1;
+            CaseOutcome::Ignored { reason: reason.to_string() }
+        } else {
+            let outcome = run_case(&case);
+            match outcome {
+                CaseOutcome::Passed => passed += 1,
+                CaseOutcome::Failed { .. } => failed += 1,
+                CaseOutcome::Panicked { .. } => panicked += 1,
+                CaseOutcome::Ignored { .. } => unreachable!("run_case never returns Ignored"),
+            }
+            outcome
+        };
+
+        details.push((case.name, outcome));
+    }
+
+    panic::set_hook(previous_hook);
+
+    Ok(ConformanceReport { passed, failed, ignored, panicked, details })
+}
+
+/// `cargo run conformance`から呼ばれるエントリポイント。`tests/cases/`と
+/// `tests/test_ignore.txt`をこのプロセスのカレントディレクトリ（通常はクレート直下）
+/// 基準で読み込み、非ignoreなケースに失敗・パニックが1件でもあれば`Err`を返す
+/// （`main`がそれをそのまま返すことで非ゼロ終了コードになる）
+pub fn run_cli() -> Result<()> {
+    let report = run(Path::new("tests/cases"), Path::new("tests/test_ignore.txt"))?;
+    report.print();
+
+    if report.is_success() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "conformance: {} failed, {} panicked (of {} non-ignored cases)",
+            report.failed,
+            report.panicked,
+            report.passed + report.failed + report.panicked
+        ))
+    }
+}
+
+/// `env:`/`expect:`行の値テキストを`Value`としてパースする。整数→真偽値→浮動小数点の
+/// 順で試し、どれにも当てはまらなければエラーにする（`3`を`3.0`ではなく`Value::Int(3)`
+/// として扱いたいため、整数判定を先に行う）
+fn parse_value_literal(text: &str) -> Result<Value> {
+    if let Ok(n) = text.parse::<i64>() {
+        return Ok(Value::Int(n));
+    }
+    if let Ok(b) = text.parse::<bool>() {
+        return Ok(Value::Bool(b));
+    }
+    if let Ok(f) = text.parse::<f64>() {
+        return Ok(Value::Float(f));
+    }
+    Err(anyhow!("invalid value literal: {}", text))
+}
+
+/// `tests/cases/`の1ファイルをパースする。フォーマット:
+/// ```text
+/// expr: y * 3 + 7
+/// env: y=10
+/// expect: 37
+/// ```
+/// エラーを期待するケースでは`expect:`の代わりに`expect_error`を書く。`env:`行は
+/// 複数書け、`#`から始まる行と空行は無視する
+fn parse_case(name: String, content: &str) -> Result<ConformanceCase> {
+    let mut expr_source = None;
+    let mut env = HashMap::new();
+    let mut expected = None;
+
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("expr:") {
+            expr_source = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("env:") {
+            let (var_name, var_value) = rest
+                .trim()
+                .split_once('=')
+                .ok_or_else(|| anyhow!("malformed `env:` line (expected name=value): {}", line))?;
+            let value = parse_value_literal(var_value.trim())
+                .with_context(|| format!("invalid env value in `{}`", line))?;
+            env.insert(var_name.trim().to_string(), value);
+        } else if line.starts_with("expect_error") {
+            expected = Some(Expected::Error);
+        } else if let Some(rest) = line.strip_prefix("expect:") {
+            let value = parse_value_literal(rest.trim())
+                .with_context(|| format!("invalid `expect:` value: {}", line))?;
+            expected = Some(Expected::Value(value));
+        } else {
+            return Err(anyhow!("unrecognized line: {}", line));
+        }
+    }
+
+    Ok(ConformanceCase {
+        name,
+        expr_source: expr_source.ok_or_else(|| anyhow!("missing `expr:` line"))?,
+        env,
+        expected: expected.ok_or_else(|| anyhow!("missing `expect:`/`expect_error` line"))?,
+    })
+}
+
+/// `test_ignore.txt`を読み込む。ファイルが存在しなければ無視リストなしとして扱う
+fn load_ignore_rules(path: &Path) -> Result<Vec<IgnoreRule>> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(Vec::new());
+    };
+
+    let mut rules = Vec::new();
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (pattern, reason) = match line.split_once('#') {
+            Some((pattern, reason)) => (pattern.trim(), reason.trim()),
+            None => (line, "no reason given"),
+        };
+        rules.push(IgnoreRule { pattern: pattern.to_string(), reason: reason.to_string() });
+    }
+    Ok(rules)
+}
+
+/// ケース名に一致する無視ルールを探す
+fn find_ignore_reason<'a>(rules: &'a [IgnoreRule], case_name: &str) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|rule| glob_match(&rule.pattern, case_name))
+        .map(|rule| rule.reason.as_str())
+}
+
+/// `*`だけを任意長のワイルドカードとしてサポートする簡易グロブ照合。スキップ対象の
+/// 指定程度であれば十分なため、クレートを引き込まず自前で実装する
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn inner(pattern: &[u8], text: &[u8]) -> bool {
+        match pattern.first() {
+            None => text.is_empty(),
+            Some(b'*') => inner(&pattern[1..], text) || (!text.is_empty() && inner(pattern, &text[1..])),
+            Some(&p) => text.first() == Some(&p) && inner(&pattern[1..], &text[1..]),
+        }
+    }
+    inner(pattern.as_bytes(), text.as_bytes())
+}
+
+/// 1ケースを`Interpreter`と`JitCompiler::execute_string`の両方で実行し、期待値と
+/// 突き合わせる。どちらかの実行がパニックしても`catch_unwind`で捕え、1件の異常が
+/// 全体の実行を止めないようにする
+fn run_case(case: &ConformanceCase) -> CaseOutcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| check_case(case))) {
+        Ok(Ok(())) => CaseOutcome::Passed,
+        Ok(Err(details)) => CaseOutcome::Failed { details },
+        Err(panic_payload) => CaseOutcome::Panicked { details: panic_message(&panic_payload) },
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// 実際の突き合わせ処理。`Err`はケース失敗の詳細メッセージ（両エンジンの不一致を
+/// まとめて報告できるよう、片方だけ落ちた場合でも最後まで両方を実行する）
+fn check_case(case: &ConformanceCase) -> Result<(), String> {
+    let interpreter_result = run_with_interpreter(case);
+    let jit_result = run_with_jit(case);
+
+    let mut problems = Vec::new();
+    if let Err(detail) = compare(&case.expected, &interpreter_result, "interpreter") {
+        problems.push(detail);
+    }
+    if let Err(detail) = compare(&case.expected, &jit_result, "jit") {
+        problems.push(detail);
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join("; "))
+    }
+}
+
+fn run_with_interpreter(case: &ConformanceCase) -> Result<Value> {
+    let mut interpreter = Interpreter::new();
+    for (name, &value) in &case.env {
+        interpreter.set_variable(name.clone(), value);
+    }
+
+    let mut parser = Parser::new(&case.expr_source)?;
+    let expr = parser.parse()?;
+    Ok(interpreter.evaluate_without_delay(&expr)?.value)
+}
+
+fn run_with_jit(case: &ConformanceCase) -> Result<Value> {
+    let mut jit = JitCompiler::new();
+    for (name, &value) in &case.env {
+        jit.execute_string(&format!("{} = {}", name, value))?;
+    }
+    Ok(jit.execute_string(&case.expr_source)?.value)
+}
+
+fn compare(expected: &Expected, actual: &Result<Value>, engine: &str) -> Result<(), String> {
+    match (expected, actual) {
+        (Expected::Value(expected_value), Ok(actual_value)) if expected_value == actual_value => Ok(()),
+        (Expected::Value(expected_value), Ok(actual_value)) => {
+            Err(format!("{}: expected {}, got {}", engine, expected_value, actual_value))
+        }
+        (Expected::Value(expected_value), Err(e)) => {
+            Err(format!("{}: expected {}, got error: {}", engine, expected_value, e))
+        }
+        (Expected::Error, Err(_)) => Ok(()),
+        (Expected::Error, Ok(actual_value)) => {
+            Err(format!("{}: expected an error, got {}", engine, actual_value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_case_reads_expr_env_and_expect() {
+        let case = parse_case(
+            "variable_env".to_string(),
+            "expr: y * 3 + 7\nenv: y=10\nexpect: 37\n",
+        )
+        .unwrap();
+
+        assert_eq!(case.expr_source, "y * 3 + 7");
+        assert_eq!(case.env.get("y"), Some(&Value::Int(10)));
+        assert_eq!(case.expected, Expected::Value(Value::Int(37)));
+    }
+
+    #[test]
+    fn test_parse_case_supports_expect_error() {
+        let case = parse_case("undefined_variable".to_string(), "expr: z + 1\nexpect_error\n").unwrap();
+        assert_eq!(case.expected, Expected::Error);
+    }
+
+    #[test]
+    fn test_parse_case_supports_bool_and_float_values() {
+        let case = parse_case("flag_env".to_string(), "expr: flag\nenv: flag=true\nexpect: true\n").unwrap();
+        assert_eq!(case.env.get("flag"), Some(&Value::Bool(true)));
+        assert_eq!(case.expected, Expected::Value(Value::Bool(true)));
+
+        let case = parse_case("float_expect".to_string(), "expr: 3.5\nexpect: 3.5\n").unwrap();
+        assert_eq!(case.expected, Expected::Value(Value::Float(3.5)));
+    }
+
+    #[test]
+    fn test_parse_case_rejects_missing_expr() {
+        assert!(parse_case("broken".to_string(), "expect: 1\n").is_err());
+    }
+
+    #[test]
+    fn test_glob_match_supports_wildcard() {
+        assert!(glob_match("float_*", "float_literal"));
+        assert!(glob_match("*", "anything"));
+        assert!(!glob_match("float_*", "int_literal"));
+    }
+
+    #[test]
+    fn test_check_case_matches_both_engines_for_passing_case() {
+        let case = ConformanceCase {
+            name: "basic".to_string(),
+            expr_source: "1 + 2 * 3".to_string(),
+            env: HashMap::new(),
+            expected: Expected::Value(Value::Int(7)),
+        };
+        assert!(check_case(&case).is_ok());
+    }
+
+    #[test]
+    fn test_check_case_reports_mismatch() {
+        let case = ConformanceCase {
+            name: "basic".to_string(),
+            expr_source: "1 + 2".to_string(),
+            env: HashMap::new(),
+            expected: Expected::Value(Value::Int(999)),
+        };
+        assert!(check_case(&case).is_err());
+    }
+}
@@ -1,16 +1,24 @@
-use crate::ast::{BinaryOp, Environment, Expr, ExecutionResult};
+use crate::ast::{hash_expr, BinaryOp, Environment, Expr, ExecutionResult, LogicalOp, UnaryOp, Value};
 use anyhow::{anyhow, Result};
+use std::collections::HashMap;
 use std::time::Instant;
 
+/// 1回の`while`ループが回せる最大反復回数。無限ループによるサーバーのハングを防ぐガード
+const MAX_LOOP_ITERATIONS: u64 = 1_000_000;
+
 /// インタープリタ
 pub struct Interpreter {
     env: Environment,
+    /// ループ本体（`Expr::While`自身）のハッシュ -> これまでの累計バックエッジ回数。
+    /// `JitCompiler`がループ単位でのホットスポット検出に使う
+    loop_counts: HashMap<u64, u64>,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
         Self {
             env: Environment::new(),
+            loop_counts: HashMap::new(),
         }
     }
 
@@ -52,9 +60,15 @@ impl Interpreter {
         })
     }
 
-    fn eval_expr(&mut self, expr: &Expr) -> Result<i64> {
+    fn eval_expr(&mut self, expr: &Expr) -> Result<Value> {
         match expr {
-            Expr::Number(n) => Ok(*n),
+            Expr::Number(n) => Ok(Value::Int(*n)),
+
+            Expr::Float(f) => Ok(Value::Float(*f)),
+
+            Expr::Bool(b) => Ok(Value::Bool(*b)),
+
+            Expr::Str(_) => Err(anyhow!("String values are not yet supported by the interpreter")),
 
             Expr::Variable(name) => {
                 self.env.get(name)
@@ -64,32 +78,7 @@ impl Interpreter {
             Expr::Binary { left, op, right } => {
                 let left_val = self.eval_expr(left)?;
                 let right_val = self.eval_expr(right)?;
-
-                match op {
-                    BinaryOp::Add => Ok(left_val + right_val),
-                    BinaryOp::Sub => Ok(left_val - right_val),
-                    BinaryOp::Mul => Ok(left_val * right_val),
-                    BinaryOp::Div => {
-                        if right_val == 0 {
-                            Err(anyhow!("Division by zero"))
-                        } else {
-                            Ok(left_val / right_val)
-                        }
-                    }
-                    BinaryOp::Mod => {
-                        if right_val == 0 {
-                            Err(anyhow!("Division by zero"))
-                        } else {
-                            Ok(left_val % right_val)
-                        }
-                    }
-                    BinaryOp::Equal => Ok(if left_val == right_val { 1 } else { 0 }),
-                    BinaryOp::NotEqual => Ok(if left_val != right_val { 1 } else { 0 }),
-                    BinaryOp::Less => Ok(if left_val < right_val { 1 } else { 0 }),
-                    BinaryOp::Greater => Ok(if left_val > right_val { 1 } else { 0 }),
-                    BinaryOp::LessEq => Ok(if left_val <= right_val { 1 } else { 0 }),
-                    BinaryOp::GreaterEq => Ok(if left_val >= right_val { 1 } else { 0 }),
-                }
+                eval_binary(op, left_val, right_val)
             }
 
             Expr::Assignment { name, value } => {
@@ -104,23 +93,23 @@ impl Interpreter {
                         if args.len() != 1 {
                             return Err(anyhow!("fib() expects 1 argument, got {}", args.len()));
                         }
-                        let n = self.eval_expr(&args[0])?;
-                        Ok(self.fibonacci(n))
+                        let n = self.eval_expr(&args[0])?.as_i64_lossy();
+                        Ok(Value::Int(self.fibonacci(n)))
                     }
                     "fact" => {
                         if args.len() != 1 {
                             return Err(anyhow!("fact() expects 1 argument, got {}", args.len()));
                         }
-                        let n = self.eval_expr(&args[0])?;
-                        Ok(self.factorial(n))
+                        let n = self.eval_expr(&args[0])?.as_i64_lossy();
+                        Ok(Value::Int(self.factorial(n)))
                     }
                     "pow" => {
                         if args.len() != 2 {
                             return Err(anyhow!("pow() expects 2 arguments, got {}", args.len()));
                         }
-                        let base = self.eval_expr(&args[0])?;
-                        let exp = self.eval_expr(&args[1])?;
-                        Ok(self.power(base, exp))
+                        let base = self.eval_expr(&args[0])?.as_i64_lossy();
+                        let exp = self.eval_expr(&args[1])?.as_i64_lossy();
+                        Ok(Value::Int(self.power(base, exp)))
                     }
                     _ => Err(anyhow!("Unknown function: {}", name)),
                 }
@@ -128,12 +117,85 @@ impl Interpreter {
 
             Expr::If { condition, true_expr, false_expr } => {
                 let cond_val = self.eval_expr(condition)?;
-                if cond_val != 0 {
+                if cond_val.is_truthy() {
                     self.eval_expr(true_expr)
                 } else {
                     self.eval_expr(false_expr)
                 }
             }
+
+            Expr::Logical { left, op, right } => {
+                let left_val = self.eval_expr(left)?;
+
+                // 短絡評価: 左辺で結果が確定する場合は右辺を評価しない
+                match op {
+                    LogicalOp::And => {
+                        if !left_val.is_truthy() {
+                            Ok(Value::Bool(false))
+                        } else {
+                            let right_val = self.eval_expr(right)?;
+                            Ok(Value::Bool(right_val.is_truthy()))
+                        }
+                    }
+                    LogicalOp::Or => {
+                        if left_val.is_truthy() {
+                            Ok(Value::Bool(true))
+                        } else {
+                            let right_val = self.eval_expr(right)?;
+                            Ok(Value::Bool(right_val.is_truthy()))
+                        }
+                    }
+                }
+            }
+
+            Expr::Unary { op, operand } => {
+                let val = self.eval_expr(operand)?;
+                match op {
+                    UnaryOp::Not => Ok(Value::Bool(!val.is_truthy())),
+                }
+            }
+
+            Expr::While { condition, body } => {
+                let loop_key = hash_expr(expr);
+                let mut result = Value::Int(0);
+                let mut iterations_this_call = 0u64;
+
+                while self.eval_expr(condition)?.is_truthy() {
+                    result = self.eval_expr(body)?;
+
+                    iterations_this_call += 1;
+                    if iterations_this_call > MAX_LOOP_ITERATIONS {
+                        return Err(anyhow!(
+                            "Loop exceeded maximum iteration count ({}); possible infinite loop",
+                            MAX_LOOP_ITERATIONS
+                        ));
+                    }
+                    *self.loop_counts.entry(loop_key).or_insert(0) += 1;
+                }
+
+                Ok(result)
+            }
+
+            Expr::Sequence(statements) => {
+                let mut result = Value::Int(0);
+                for statement in statements {
+                    result = self.eval_expr(statement)?;
+                }
+                Ok(result)
+            }
+
+            Expr::Fallback { primary, fallback } => {
+                // フォールバックが意味を持つのは「未束縛の変数」の場合だけなので、
+                // ここだけ`self.env.get`を直接見る。それ以外の式はそのまま評価し、
+                // 起きうるエラー（未知の関数など）を握りつぶさない
+                match primary.as_ref() {
+                    Expr::Variable(name) => match self.env.get(name) {
+                        Some(value) => Ok(value),
+                        None => self.eval_expr(fallback),
+                    },
+                    _ => self.eval_expr(primary),
+                }
+            }
         }
     }
 
@@ -169,6 +231,7 @@ impl Interpreter {
     /// 環境をリセット
     pub fn reset(&mut self) {
         self.env = Environment::new();
+        self.loop_counts.clear();
     }
 
     /// 環境を取得
@@ -177,9 +240,15 @@ impl Interpreter {
     }
 
     /// 変数を設定
-    pub fn set_variable(&mut self, name: String, value: i64) {
+    pub fn set_variable(&mut self, name: String, value: Value) {
         self.env.set(name, value);
     }
+
+    /// 指定したループ（`Expr::While`のハッシュ）がこれまでに回したバックエッジの累計回数を取得する。
+    /// `JitCompiler`が「ループ単位」のホットスポット検出に使う
+    pub fn loop_iteration_count(&self, loop_hash: u64) -> u64 {
+        self.loop_counts.get(&loop_hash).copied().unwrap_or(0)
+    }
 }
 
 impl Default for Interpreter {
@@ -188,6 +257,69 @@ impl Default for Interpreter {
     }
 }
 
+/// 二項演算の評価。どちらかの被演算数が`Float`ならもう一方も`f64`に昇格して計算する
+/// （Int+Float→Float）。比較演算は常に`Bool`を返す
+fn eval_binary(op: &BinaryOp, left: Value, right: Value) -> Result<Value> {
+    match op {
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul => Ok(numeric_arith(op, left, right)),
+
+        BinaryOp::Div => {
+            if matches!(left, Value::Float(_)) || matches!(right, Value::Float(_)) {
+                let divisor = right.as_f64();
+                if divisor == 0.0 {
+                    Err(anyhow!("Division by zero"))
+                } else {
+                    Ok(Value::Float(left.as_f64() / divisor))
+                }
+            } else {
+                let divisor = right.as_i64_lossy();
+                if divisor == 0 {
+                    Err(anyhow!("Division by zero"))
+                } else {
+                    Ok(Value::Int(left.as_i64_lossy() / divisor))
+                }
+            }
+        }
+
+        BinaryOp::Mod => {
+            let divisor = right.as_i64_lossy();
+            if divisor == 0 {
+                Err(anyhow!("Division by zero"))
+            } else {
+                Ok(Value::Int(left.as_i64_lossy() % divisor))
+            }
+        }
+
+        BinaryOp::Equal => Ok(Value::Bool(left.as_f64() == right.as_f64())),
+        BinaryOp::NotEqual => Ok(Value::Bool(left.as_f64() != right.as_f64())),
+        BinaryOp::Less => Ok(Value::Bool(left.as_f64() < right.as_f64())),
+        BinaryOp::Greater => Ok(Value::Bool(left.as_f64() > right.as_f64())),
+        BinaryOp::LessEq => Ok(Value::Bool(left.as_f64() <= right.as_f64())),
+        BinaryOp::GreaterEq => Ok(Value::Bool(left.as_f64() >= right.as_f64())),
+    }
+}
+
+/// Add/Sub/Mulの共通実装。どちらかがFloatならFloat同士として計算し、それ以外はInt同士で計算する
+fn numeric_arith(op: &BinaryOp, left: Value, right: Value) -> Value {
+    if matches!(left, Value::Float(_)) || matches!(right, Value::Float(_)) {
+        let (l, r) = (left.as_f64(), right.as_f64());
+        Value::Float(match op {
+            BinaryOp::Add => l + r,
+            BinaryOp::Sub => l - r,
+            BinaryOp::Mul => l * r,
+            _ => unreachable!(),
+        })
+    } else {
+        let (l, r) = (left.as_i64_lossy(), right.as_i64_lossy());
+        Value::Int(match op {
+            BinaryOp::Add => l + r,
+            BinaryOp::Sub => l - r,
+            BinaryOp::Mul => l * r,
+            _ => unreachable!(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -308,6 +440,70 @@ mod tests {
         assert_eq!(result.value, 20);
     }
 
+    #[test]
+    fn test_logical_short_circuit() {
+        let mut interpreter = Interpreter::new();
+
+        // false && fib(...) should not evaluate the right side (undefined function would error)
+        let expr = Expr::Logical {
+            left: Box::new(Expr::Number(0)),
+            op: crate::ast::LogicalOp::And,
+            right: Box::new(Expr::FunctionCall {
+                name: "undefined_fn".to_string(),
+                args: vec![],
+            }),
+        };
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, crate::ast::Value::Bool(false));
+
+        // true || fib(...) should not evaluate the right side either
+        let expr = Expr::Logical {
+            left: Box::new(Expr::Number(1)),
+            op: crate::ast::LogicalOp::Or,
+            right: Box::new(Expr::FunctionCall {
+                name: "undefined_fn".to_string(),
+                args: vec![],
+            }),
+        };
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, crate::ast::Value::Bool(true));
+    }
+
+    #[test]
+    fn test_unary_not() {
+        let mut interpreter = Interpreter::new();
+        let expr = Expr::Unary {
+            op: crate::ast::UnaryOp::Not,
+            operand: Box::new(Expr::Number(0)),
+        };
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, crate::ast::Value::Bool(true));
+    }
+
+    #[test]
+    fn test_while_loop_accumulates_sum() {
+        let mut interpreter = Interpreter::new();
+        interpreter.evaluate(&Parser::new("i = 0").unwrap().parse().unwrap()).unwrap();
+        interpreter.evaluate(&Parser::new("sum = 0").unwrap().parse().unwrap()).unwrap();
+
+        let mut parser = Parser::new("while(i < 5, i = i + 1; sum = sum + i)").unwrap();
+        let expr = parser.parse().unwrap();
+        let result = interpreter.evaluate(&expr).unwrap();
+
+        assert_eq!(result.value, 15); // 1+2+3+4+5
+        assert_eq!(result.environment["sum"], 15);
+        assert_eq!(result.environment["i"], 5);
+    }
+
+    #[test]
+    fn test_while_loop_never_executed_returns_zero() {
+        let mut interpreter = Interpreter::new();
+        let mut parser = Parser::new("while(0, 1 + 1)").unwrap();
+        let expr = parser.parse().unwrap();
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, 0);
+    }
+
     #[test]
     fn test_complex_expression() {
         let mut interpreter = Interpreter::new();
@@ -333,4 +529,89 @@ mod tests {
         let result3 = interpreter.evaluate(&expr3).unwrap();
         assert_eq!(result3.value, 25); // 20 + 5 = 25
     }
+
+    #[test]
+    fn test_float_and_bool_literals_are_preserved() {
+        let mut interpreter = Interpreter::new();
+
+        let result = interpreter.evaluate(&Expr::Float(3.5)).unwrap();
+        assert_eq!(result.value, Value::Float(3.5));
+
+        let result = interpreter.evaluate(&Expr::Bool(true)).unwrap();
+        assert_eq!(result.value, Value::Bool(true));
+    }
+
+    #[test]
+    fn test_comparisons_yield_bool() {
+        let mut interpreter = Interpreter::new();
+        let mut parser = Parser::new("5 > 3").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, Value::Bool(true));
+    }
+
+    #[test]
+    fn test_int_and_float_arithmetic_promotes_to_float() {
+        let mut interpreter = Interpreter::new();
+        let mut parser = Parser::new("1 + 2.5").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, Value::Float(3.5));
+    }
+
+    #[test]
+    fn test_division_by_float_promotes_to_float() {
+        let mut interpreter = Interpreter::new();
+        let mut parser = Parser::new("5 / 2.0").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, Value::Float(2.5));
+    }
+
+    #[test]
+    fn test_fallback_is_used_when_variable_is_unbound() {
+        let mut interpreter = Interpreter::new();
+        let mut parser = Parser::new("x ?? 5").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, Value::Int(5));
+    }
+
+    #[test]
+    fn test_fallback_is_ignored_when_variable_is_bound() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("x".to_string(), Value::Int(42));
+        let mut parser = Parser::new("x ?? 5").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, Value::Int(42));
+    }
+
+    #[test]
+    fn test_fallback_right_side_is_not_evaluated_when_unnecessary() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("x".to_string(), Value::Int(1));
+        // `fact`の呼び出し自体は正しく動くはずだが、万一右辺が評価されてしまうと
+        // 未知の関数呼び出しエラーになるので、右辺が評価されていないことの検証になる
+        let mut parser = Parser::new("x ?? unknown_fn(1)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, Value::Int(1));
+    }
+
+    #[test]
+    fn test_default_call_syntax_behaves_like_nullish_operator() {
+        let mut interpreter = Interpreter::new();
+        let mut parser = Parser::new("default(y, 7)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = interpreter.evaluate(&expr).unwrap();
+        assert_eq!(result.value, Value::Int(7));
+    }
 }
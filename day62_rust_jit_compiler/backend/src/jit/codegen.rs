@@ -1,29 +0,0 @@
-// x86-64 コード生成器（Phase 3で実装予定）
-
-use crate::ast::Expr;
-use anyhow::Result;
-
-/// x86-64マシンコード生成器
-pub struct X86CodeGenerator {
-    // Phase 3で実装予定
-}
-
-impl X86CodeGenerator {
-    pub fn new() -> Self {
-        Self {
-            // Phase 3で実装予定
-        }
-    }
-
-    /// ASTからx86-64マシンコードを生成
-    pub fn generate(&self, _expr: &Expr) -> Result<Vec<u8>> {
-        // Phase 3で実装予定
-        todo!("x86-64 code generation will be implemented in Phase 3")
-    }
-}
-
-impl Default for X86CodeGenerator {
-    fn default() -> Self {
-        Self::new()
-    }
-}
\ No newline at end of file
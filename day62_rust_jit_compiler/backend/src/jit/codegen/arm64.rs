@@ -0,0 +1,841 @@
+// AArch64 (ARM64) 向けコード生成器
+//
+// x86-64が可変長のCISC命令なのに対し、AArch64は32ビット固定長命令を持つロード/ストア型
+// (RISC) のISAである。同じASTパイプライン（算術式をIRへ下げて線形スキャンでレジスタ割り当て
+// する方式）が全く性質の異なる命令セットにもそのまま適用できることを示すための第2バックエンド。
+
+use super::{CodeGenerator, CompiledFunction};
+use crate::ast::{BinaryOp, Expr};
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// AArch64の汎用レジスタ。X1-X4を線形スキャンの割り当て対象、X0/X9を算術演算の一時置き場
+/// （x86版のRAX/RCXに相当）、X8を除算の商を一時的に保持する三つ目の一時レジスタ（剰余計算で
+/// 被除数を保持するレジスタを壊さないために必要）、X29をフレームポインタとして使う
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Register {
+    X0 = 0,
+    X1 = 1,
+    X2 = 2,
+    X3 = 3,
+    X4 = 4,
+    X8 = 8,
+    X9 = 9,
+    Fp = 29, // X29: フレームポインタ（x86のrbpに相当）
+}
+
+/// 算術式を仮想レジスタで表したIR命令（x86版と同じ構造。ISAが変わっても下げ方は共通）
+#[derive(Debug, Clone)]
+enum IrInstr {
+    LoadImm { dest: VReg, value: i64 },
+    LoadVar { dest: VReg, name: String },
+    BinOp { dest: VReg, op: BinaryOp, lhs: VReg, rhs: VReg },
+    Nested { dest: VReg, expr: Box<Expr> },
+}
+
+type VReg = usize;
+
+/// 割り当て結果: 物理レジスタか、フレームポインタ相対スタックスロットか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Location {
+    Reg(Register),
+    Stack(i32),
+}
+
+/// 仮想レジスタの生存区間
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    vreg: VReg,
+    start: usize,
+    end: usize,
+}
+
+/// cmp直後のcset命令で使う条件コード（AArch64の4ビット条件コード）
+enum Cond {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+impl Cond {
+    /// AArch64の条件コード（4ビット）
+    fn code(&self) -> u32 {
+        match self {
+            Cond::Eq => 0b0000,
+            Cond::Ne => 0b0001,
+            Cond::Ge => 0b1010,
+            Cond::Lt => 0b1011,
+            Cond::Gt => 0b1100,
+            Cond::Le => 0b1101,
+        }
+    }
+
+    /// 条件を反転する（CSETがCSINCの「偽のときだけインクリメント」を使うため必要）
+    fn inverted_code(&self) -> u32 {
+        self.code() ^ 1
+    }
+}
+
+/// AArch64マシンコード生成器
+///
+/// x86版と同じく算術式（数値・変数・二項演算）を仮想レジスタのフラットなIR命令列へ下げ、
+/// 線形スキャンでX1-X4の空きレジスタに割り当てる。AArch64のsdiv/msubはx86のidivと違い
+/// 特定のレジスタを占有しないため、x86版の`has_div_mod`分岐のような除算専用の予約は不要。
+/// 結果は常にX0に残る。
+pub struct Arm64CodeGenerator {
+    code: Vec<u8>,
+    variables: HashMap<String, i32>,
+    stack_offset: i32,
+    next_vreg: VReg,
+}
+
+impl Arm64CodeGenerator {
+    pub fn new() -> Self {
+        Self {
+            code: Vec::new(),
+            variables: HashMap::new(),
+            stack_offset: 0,
+            next_vreg: 0,
+        }
+    }
+
+    /// 式をコンパイル（結果はX0に格納）
+    fn compile_expr(&mut self, expr: &Expr) -> Result<()> {
+        match expr {
+            Expr::Number(_) | Expr::Bool(_) | Expr::Variable(_) | Expr::Binary { .. } => {
+                self.compile_arithmetic(expr)
+            }
+
+            Expr::Assignment { name, value } => {
+                self.compile_expr(value)?;
+                let offset = self.allocate_variable(name.clone());
+                self.emit_store(offset, Register::X0);
+                Ok(())
+            }
+
+            Expr::If { condition, true_expr, false_expr } => {
+                self.compile_expr(condition)?;
+                let false_jump_pos = self.emit_cbz_placeholder(Register::X0);
+
+                self.compile_expr(true_expr)?;
+                let end_jump_pos = self.emit_b_placeholder();
+
+                let false_label_pos = self.code.len();
+                self.patch_cbz(false_jump_pos, false_label_pos);
+
+                self.compile_expr(false_expr)?;
+
+                let end_label_pos = self.code.len();
+                self.patch_b(end_jump_pos, end_label_pos);
+                Ok(())
+            }
+
+            Expr::While { condition, body } => {
+                // x86版と同じく、ループを抜ける最後の条件判定でX0が条件の値に
+                // 上書きされてしまうため、直近のbody評価値を専用のスピルスロットに
+                // 退避しておき、ループを抜けた後にX0へ復元する
+                let result_slot = self.allocate_spill_slot();
+                self.emit_mov_imm(Register::X0, 0);
+                self.emit_store(result_slot, Register::X0);
+
+                let loop_start = self.code.len();
+                self.compile_expr(condition)?;
+                let exit_jump_pos = self.emit_cbz_placeholder(Register::X0);
+
+                self.compile_expr(body)?;
+                self.emit_store(result_slot, Register::X0);
+
+                let back_jump_pos = self.emit_b_placeholder();
+                self.patch_b(back_jump_pos, loop_start);
+
+                let exit_pos = self.code.len();
+                self.patch_cbz(exit_jump_pos, exit_pos);
+
+                self.emit_load(Register::X0, result_slot);
+                Ok(())
+            }
+
+            Expr::Sequence(statements) => {
+                for statement in statements {
+                    self.compile_expr(statement)?;
+                }
+                Ok(())
+            }
+
+            // 関数呼び出しはx86-64バックエンドでのみSystem V AMD64呼び出し規約で
+            // サポートしている。AArch64側（AAPCS64の引数レジスタやcall/ret相当の
+            // 符号化）への移植は今後の課題
+            Expr::FunctionCall { .. } => Err(anyhow!("Function calls are unsupported in Phase 3")),
+            Expr::Logical { .. } => Err(anyhow!("Logical operators are unsupported in Phase 3")),
+            Expr::Unary { .. } => Err(anyhow!("Unary operators are unsupported in Phase 3")),
+            Expr::Float(_) => Err(anyhow!("Floating-point literals are unsupported in Phase 3")),
+            Expr::Str(_) => Err(anyhow!("String literals are unsupported in Phase 3")),
+            // `JitCompiler::resolve_fallbacks`がコード生成前に`Fallback`をどちらかの
+            // 枝へ解決し尽くしているはずなので、ここに到達するのはそのパスを経由しない
+            // 呼び出し元の不具合のみ
+            Expr::Fallback { .. } => Err(anyhow!("Fallback should have been resolved before codegen")),
+        }
+    }
+
+    /// 数値・変数・二項演算からなる算術式をIRへ下げ、レジスタ割り当てしてコンパイルする
+    fn compile_arithmetic(&mut self, expr: &Expr) -> Result<()> {
+        self.next_vreg = 0;
+        let mut instrs = Vec::new();
+        let root = self.lower_arithmetic(expr, &mut instrs)?;
+
+        let intervals = compute_intervals(&instrs, root);
+        // `Nested`（if/while）は`compile_expr`へ丸ごと委譲するため、中でX1-X4の割り当て
+        // プールを自由に使い潰す。このプールを割り当て対象にしている以上、`Nested`をまたいで
+        // 生存する区間は物理レジスタに置いたままにできない（x86版`allocate_registers`と
+        // 同じクロバーポイント判定。詳細はそちらのコメント参照）
+        let clobber_points: Vec<usize> = instrs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, instr)| matches!(instr, IrInstr::Nested { .. }).then_some(i))
+            .collect();
+        let locations = self.allocate_registers(&intervals, &clobber_points);
+
+        for instr in &instrs {
+            self.emit_ir_instr(instr, &locations)?;
+        }
+
+        self.ensure_in_x0(locations[&root]);
+        Ok(())
+    }
+
+    fn lower_arithmetic(&mut self, expr: &Expr, instrs: &mut Vec<IrInstr>) -> Result<VReg> {
+        match expr {
+            Expr::Number(n) => {
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::LoadImm { dest, value: *n });
+                Ok(dest)
+            }
+
+            // 真偽値は0/1の整数として表現する（比較演算の結果と同じレジスタ上の形）
+            Expr::Bool(b) => {
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::LoadImm { dest, value: if *b { 1 } else { 0 } });
+                Ok(dest)
+            }
+
+            Expr::Variable(name) => {
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::LoadVar { dest, name: name.clone() });
+                Ok(dest)
+            }
+
+            Expr::Binary { left, op, right } => {
+                let lhs = self.lower_arithmetic(left, instrs)?;
+                let rhs = self.lower_arithmetic(right, instrs)?;
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::BinOp { dest, op: op.clone(), lhs, rhs });
+                Ok(dest)
+            }
+
+            Expr::If { .. } | Expr::While { .. } => {
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::Nested { dest, expr: Box::new(expr.clone()) });
+                Ok(dest)
+            }
+
+            Expr::Assignment { .. } => {
+                Err(anyhow!("Assignment is unsupported as an arithmetic operand in Phase 3"))
+            }
+            Expr::Sequence(_) => {
+                Err(anyhow!("Sequence is unsupported as an arithmetic operand in Phase 3"))
+            }
+            Expr::FunctionCall { .. } => Err(anyhow!("Function calls are unsupported in Phase 3")),
+            Expr::Logical { .. } => Err(anyhow!("Logical operators are unsupported in Phase 3")),
+            Expr::Unary { .. } => Err(anyhow!("Unary operators are unsupported in Phase 3")),
+            Expr::Float(_) => Err(anyhow!("Floating-point literals are unsupported in Phase 3")),
+            Expr::Str(_) => Err(anyhow!("String literals are unsupported in Phase 3")),
+            // `JitCompiler::resolve_fallbacks`がコード生成前に`Fallback`をどちらかの
+            // 枝へ解決し尽くしているはずなので、ここに到達するのはそのパスを経由しない
+            // 呼び出し元の不具合のみ
+            Expr::Fallback { .. } => Err(anyhow!("Fallback should have been resolved before codegen")),
+        }
+    }
+
+    fn alloc_vreg(&mut self) -> VReg {
+        let vreg = self.next_vreg;
+        self.next_vreg += 1;
+        vreg
+    }
+
+    /// 線形スキャンでX1-X4を割り当てる。`clobber_points`をまたいで生存する区間は、
+    /// `Nested`の呼び出し先が割り当て対象レジスタを自由に使い潰すため物理レジスタに
+    /// 置かずスタックへスピルを強制する
+    fn allocate_registers(&mut self, intervals: &[Interval], clobber_points: &[usize]) -> HashMap<VReg, Location> {
+        let pool_order: &[Register] = &[Register::X1, Register::X2, Register::X3, Register::X4];
+        let mut free: Vec<Register> = pool_order.to_vec();
+        let mut active: Vec<(Interval, Register)> = Vec::new();
+        let mut assignment: HashMap<VReg, Location> = HashMap::new();
+
+        for interval in intervals {
+            active.retain(|(iv, reg)| {
+                if iv.end < interval.start {
+                    free.push(*reg);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            let crosses_clobber_point = clobber_points
+                .iter()
+                .any(|&point| interval.start < point && point < interval.end);
+            if crosses_clobber_point {
+                let offset = self.allocate_spill_slot();
+                assignment.insert(interval.vreg, Location::Stack(offset));
+                continue;
+            }
+
+            if let Some(reg) = free.pop() {
+                assignment.insert(interval.vreg, Location::Reg(reg));
+                active.push((*interval, reg));
+                active.sort_by_key(|(iv, _)| iv.end);
+            } else if let Some(&(spill_candidate, spill_reg)) = active.last() {
+                if spill_candidate.end > interval.end {
+                    active.pop();
+                    let offset = self.allocate_spill_slot();
+                    assignment.insert(spill_candidate.vreg, Location::Stack(offset));
+                    assignment.insert(interval.vreg, Location::Reg(spill_reg));
+                    active.push((*interval, spill_reg));
+                    active.sort_by_key(|(iv, _)| iv.end);
+                } else {
+                    let offset = self.allocate_spill_slot();
+                    assignment.insert(interval.vreg, Location::Stack(offset));
+                }
+            } else {
+                let offset = self.allocate_spill_slot();
+                assignment.insert(interval.vreg, Location::Stack(offset));
+            }
+        }
+
+        assignment
+    }
+
+    fn emit_ir_instr(&mut self, instr: &IrInstr, locations: &HashMap<VReg, Location>) -> Result<()> {
+        match instr {
+            IrInstr::LoadImm { dest, value } => {
+                match locations[dest] {
+                    Location::Reg(r) => self.emit_mov_imm(r, *value),
+                    Location::Stack(off) => {
+                        self.emit_mov_imm(Register::X0, *value);
+                        self.emit_store(off, Register::X0);
+                    }
+                }
+                Ok(())
+            }
+
+            IrInstr::LoadVar { dest, name } => {
+                // x86版と同様、この式の中で一度も代入されていない変数は外部から
+                // シードされる変数とみなし、その場でスタックスロットを割り当てる
+                let var_offset = self.allocate_variable(name.clone());
+                match locations[dest] {
+                    Location::Reg(r) => self.emit_load(r, var_offset),
+                    Location::Stack(off) => {
+                        self.emit_load(Register::X0, var_offset);
+                        self.emit_store(off, Register::X0);
+                    }
+                }
+                Ok(())
+            }
+
+            IrInstr::BinOp { dest, op, lhs, rhs } => {
+                let dest_loc = locations[dest];
+                let lhs_loc = locations[lhs];
+                let rhs_loc = locations[rhs];
+
+                match op {
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul => {
+                        let lhs_reg = self.materialize(lhs_loc, Register::X0);
+                        let rhs_reg = self.materialize(rhs_loc, Register::X9);
+                        match op {
+                            BinaryOp::Add => self.emit_add(Register::X0, lhs_reg, rhs_reg),
+                            BinaryOp::Sub => self.emit_sub(Register::X0, lhs_reg, rhs_reg),
+                            BinaryOp::Mul => self.emit_mul(Register::X0, lhs_reg, rhs_reg),
+                            _ => unreachable!(),
+                        }
+                        self.move_result_to(dest_loc, Register::X0);
+                    }
+
+                    BinaryOp::Div | BinaryOp::Mod => {
+                        let lhs_reg = self.materialize(lhs_loc, Register::X0);
+                        let rhs_reg = self.materialize(rhs_loc, Register::X9);
+                        match op {
+                            BinaryOp::Div => self.emit_sdiv(Register::X0, lhs_reg, rhs_reg),
+                            BinaryOp::Mod => {
+                                // 剰余 = n - (n / m) * m。AArch64にはidiv+残り専用レジスタの
+                                // ような仕組みがなく、sdivの商からmsubで直接求める。商はX8に
+                                // 計算し、被除数を保持するlhs_reg（X0の場合もある）を壊さない
+                                self.emit_sdiv(Register::X8, lhs_reg, rhs_reg);
+                                self.emit_msub(Register::X0, Register::X8, rhs_reg, lhs_reg);
+                            }
+                            _ => unreachable!(),
+                        }
+                        self.move_result_to(dest_loc, Register::X0);
+                    }
+
+                    BinaryOp::Equal
+                    | BinaryOp::NotEqual
+                    | BinaryOp::Less
+                    | BinaryOp::Greater
+                    | BinaryOp::LessEq
+                    | BinaryOp::GreaterEq => {
+                        let lhs_reg = self.materialize(lhs_loc, Register::X0);
+                        let rhs_reg = self.materialize(rhs_loc, Register::X9);
+                        self.emit_cmp(lhs_reg, rhs_reg);
+                        let cond = match op {
+                            BinaryOp::Equal => Cond::Eq,
+                            BinaryOp::NotEqual => Cond::Ne,
+                            BinaryOp::Less => Cond::Lt,
+                            BinaryOp::Greater => Cond::Gt,
+                            BinaryOp::LessEq => Cond::Le,
+                            BinaryOp::GreaterEq => Cond::Ge,
+                            _ => unreachable!(),
+                        };
+                        self.emit_cset(Register::X0, cond);
+                        self.move_result_to(dest_loc, Register::X0);
+                    }
+                }
+                Ok(())
+            }
+
+            IrInstr::Nested { dest, expr } => {
+                self.compile_expr(expr)?;
+                self.move_result_to(locations[dest], Register::X0);
+                Ok(())
+            }
+        }
+    }
+
+    fn materialize(&mut self, loc: Location, scratch: Register) -> Register {
+        match loc {
+            Location::Reg(r) => r,
+            Location::Stack(off) => {
+                self.emit_load(scratch, off);
+                scratch
+            }
+        }
+    }
+
+    fn move_result_to(&mut self, dest: Location, result_reg: Register) {
+        match dest {
+            Location::Reg(r) if r != result_reg => self.emit_mov_reg_reg(r, result_reg),
+            Location::Reg(_) => {}
+            Location::Stack(off) => self.emit_store(off, result_reg),
+        }
+    }
+
+    /// 式全体の最終結果をX0へ集約する（compile_expr側の「結果は常にX0」という契約を保つ）
+    fn ensure_in_x0(&mut self, loc: Location) {
+        match loc {
+            Location::Reg(Register::X0) => {}
+            Location::Reg(r) => self.emit_mov_reg_reg(Register::X0, r),
+            Location::Stack(off) => self.emit_load(Register::X0, off),
+        }
+    }
+
+    fn allocate_variable(&mut self, name: String) -> i32 {
+        if let Some(&offset) = self.variables.get(&name) {
+            offset
+        } else {
+            self.stack_offset -= 8;
+            self.variables.insert(name, self.stack_offset);
+            self.stack_offset
+        }
+    }
+
+    fn allocate_spill_slot(&mut self) -> i32 {
+        self.stack_offset -= 8;
+        self.stack_offset
+    }
+
+    // === AArch64命令エミット関数（32ビット固定長。常に4バイトずつ積む） ===
+
+    fn push_word(&mut self, word: u32) {
+        self.code.extend_from_slice(&word.to_le_bytes());
+    }
+
+    /// 関数プロローグ。x86版がスタック確保(`sub rsp`)を省略しているのと同様、
+    /// ローカル変数用の領域確保も省略し、フレームポインタの確立だけを行う
+    fn emit_prologue(&mut self) {
+        self.push_word(0x910003fd); // mov x29, sp
+    }
+
+    /// 関数エピローグ
+    fn emit_epilogue(&mut self) {
+        self.push_word(0xd65f03c0); // ret
+    }
+
+    /// movz/movkでレジスタへ64ビット即値をロードする（常に4命令。サイズ最適化はしない）
+    fn emit_mov_imm(&mut self, dest: Register, value: i64) {
+        let bits = value as u64;
+        let rd = dest as u32;
+        let chunk = |shift: u32| ((bits >> shift) & 0xffff) as u32;
+
+        self.push_word((1 << 31) | (0b10 << 29) | (0b100101 << 23) | (0 << 21) | (chunk(0) << 5) | rd);
+        self.push_word((1 << 31) | (0b11 << 29) | (0b100101 << 23) | (1 << 21) | (chunk(16) << 5) | rd);
+        self.push_word((1 << 31) | (0b11 << 29) | (0b100101 << 23) | (2 << 21) | (chunk(32) << 5) | rd);
+        self.push_word((1 << 31) | (0b11 << 29) | (0b100101 << 23) | (3 << 21) | (chunk(48) << 5) | rd);
+    }
+
+    /// mov dest, src (ORR dest, XZR, src のエイリアス)
+    fn emit_mov_reg_reg(&mut self, dest: Register, src: Register) {
+        let word = (1 << 31) | (0b01 << 29) | (0b01010 << 24) | ((src as u32) << 16) | (31 << 5) | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// add dest, lhs, rhs
+    fn emit_add(&mut self, dest: Register, lhs: Register, rhs: Register) {
+        let word = (1 << 31) | (0 << 30) | (0b01011 << 24) | ((rhs as u32) << 16) | ((lhs as u32) << 5) | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// sub dest, lhs, rhs
+    fn emit_sub(&mut self, dest: Register, lhs: Register, rhs: Register) {
+        let word = (1 << 31) | (1 << 30) | (0b01011 << 24) | ((rhs as u32) << 16) | ((lhs as u32) << 5) | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// mul dest, lhs, rhs (MADD dest, lhs, rhs, XZR のエイリアス)
+    fn emit_mul(&mut self, dest: Register, lhs: Register, rhs: Register) {
+        let word = (1 << 31)
+            | (0b11011 << 24)
+            | ((rhs as u32) << 16)
+            | (31 << 10) // Ra = XZR
+            | ((lhs as u32) << 5)
+            | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// sdiv dest, lhs, rhs (符号付き除算)
+    fn emit_sdiv(&mut self, dest: Register, lhs: Register, rhs: Register) {
+        let word = (1 << 31)
+            | (0b11010110 << 21)
+            | ((rhs as u32) << 16)
+            | (0b000011 << 10)
+            | ((lhs as u32) << 5)
+            | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// msub dest, lhs, rhs, acc (dest = acc - lhs*rhs)。除算と組み合わせて剰余を計算する
+    fn emit_msub(&mut self, dest: Register, lhs: Register, rhs: Register, acc: Register) {
+        let word = (1 << 31)
+            | (0b11011 << 24)
+            | ((rhs as u32) << 16)
+            | (1 << 15) // o0=1 (MSUB)
+            | ((acc as u32) << 10)
+            | ((lhs as u32) << 5)
+            | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// cmp lhs, rhs (SUBS XZR, lhs, rhs のエイリアス。比較結果はNZCVフラグに残る)
+    fn emit_cmp(&mut self, lhs: Register, rhs: Register) {
+        let word = (1 << 31) | (1 << 30) | (1 << 29) | (0b01011 << 24) | ((rhs as u32) << 16) | ((lhs as u32) << 5) | 31;
+        self.push_word(word);
+    }
+
+    /// cset dest, cond（直前のcmpの結果をもとにdestへ0/1を書き込む）
+    fn emit_cset(&mut self, dest: Register, cond: Cond) {
+        // CSET dest, cond は CSINC dest, XZR, XZR, invert(cond) のエイリアス
+        let word = (0b11010100 << 21) | (31 << 16) | (cond.inverted_code() << 12) | (0b01 << 10) | (31 << 5) | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// add dest, src, #imm (imm12、0以上の即値のみ)
+    fn emit_add_imm(&mut self, dest: Register, src: Register, imm: u32) {
+        let word = (1 << 31) | (0b10001 << 24) | ((imm & 0xfff) << 10) | ((src as u32) << 5) | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// sub dest, src, #imm (imm12、0以上の即値のみ)
+    fn emit_sub_imm(&mut self, dest: Register, src: Register, imm: u32) {
+        let word = (1 << 31) | (1 << 30) | (0b10001 << 24) | ((imm & 0xfff) << 10) | ((src as u32) << 5) | (dest as u32);
+        self.push_word(word);
+    }
+
+    /// offsetがstur/ldurのimm9範囲(±256)に収まらない場合、X9に実効アドレスを計算して使う。
+    /// 戻り値は(ベースレジスタ, そのベースからの符号付きオフセット)
+    fn resolve_fp_offset(&mut self, offset: i32) -> (Register, i32) {
+        if (-256..=255).contains(&offset) {
+            (Register::Fp, offset)
+        } else if offset < 0 {
+            self.emit_sub_imm(Register::X9, Register::Fp, (-offset) as u32);
+            (Register::X9, 0)
+        } else {
+            self.emit_add_imm(Register::X9, Register::Fp, offset as u32);
+            (Register::X9, 0)
+        }
+    }
+
+    /// stur src, [fp, #offset] (フレームポインタ相対ストア、符号付き9ビットオフセット)
+    fn emit_store(&mut self, offset: i32, src: Register) {
+        let (base, off) = self.resolve_fp_offset(offset);
+        self.emit_stur_ldur(src, base, off, 0b00);
+    }
+
+    /// ldur dest, [fp, #offset]
+    fn emit_load(&mut self, dest: Register, offset: i32) {
+        let (base, off) = self.resolve_fp_offset(offset);
+        self.emit_stur_ldur(dest, base, off, 0b01);
+    }
+
+    fn emit_stur_ldur(&mut self, rt: Register, rn: Register, simm9: i32, opc: u32) {
+        let word = (0b11 << 30)
+            | (0b111 << 27)
+            | (0b00 << 24)
+            | (opc << 22)
+            | (((simm9 as u32) & 0x1ff) << 12)
+            | ((rn as u32) << 5)
+            | (rt as u32);
+        self.push_word(word);
+    }
+
+    /// cbz rt, label (プレースホルダー) - パッチ対象のワード位置を返す
+    fn emit_cbz_placeholder(&mut self, rt: Register) -> usize {
+        let pos = self.code.len();
+        self.push_word((1 << 31) | (0b0110100 << 24) | (rt as u32));
+        pos
+    }
+
+    /// b label (プレースホルダー) - パッチ対象のワード位置を返す
+    fn emit_b_placeholder(&mut self) -> usize {
+        let pos = self.code.len();
+        self.push_word(0b000101 << 26);
+        pos
+    }
+
+    /// cbz命令のimm19フィールドをパッチする（相対ワードオフセット、命令自身の位置から数える）
+    fn patch_cbz(&mut self, jump_pos: usize, target_pos: usize) {
+        let word_offset = ((target_pos as i64) - (jump_pos as i64)) / 4;
+        let imm19 = (word_offset as i32) & 0x7ffff;
+        let mut word = u32::from_le_bytes(self.code[jump_pos..jump_pos + 4].try_into().unwrap());
+        word |= (imm19 as u32) << 5;
+        self.code[jump_pos..jump_pos + 4].copy_from_slice(&word.to_le_bytes());
+    }
+
+    /// b命令のimm26フィールドをパッチする
+    fn patch_b(&mut self, jump_pos: usize, target_pos: usize) {
+        let word_offset = ((target_pos as i64) - (jump_pos as i64)) / 4;
+        let imm26 = (word_offset as i32) & 0x3ffffff;
+        let mut word = u32::from_le_bytes(self.code[jump_pos..jump_pos + 4].try_into().unwrap());
+        word |= imm26 as u32;
+        self.code[jump_pos..jump_pos + 4].copy_from_slice(&word.to_le_bytes());
+    }
+}
+
+/// IR命令列から各仮想レジスタの生存区間を求める（x86版と同じロジック）
+fn compute_intervals(instrs: &[IrInstr], root: VReg) -> Vec<Interval> {
+    let mut starts: HashMap<VReg, usize> = HashMap::new();
+    let mut ends: HashMap<VReg, usize> = HashMap::new();
+
+    for (i, instr) in instrs.iter().enumerate() {
+        match instr {
+            IrInstr::LoadImm { dest, .. } | IrInstr::LoadVar { dest, .. } | IrInstr::Nested { dest, .. } => {
+                starts.entry(*dest).or_insert(i);
+            }
+            IrInstr::BinOp { dest, lhs, rhs, .. } => {
+                starts.entry(*dest).or_insert(i);
+                ends.insert(*lhs, i);
+                ends.insert(*rhs, i);
+            }
+        }
+    }
+    ends.insert(root, instrs.len());
+
+    let mut intervals: Vec<Interval> = starts
+        .into_iter()
+        .map(|(vreg, start)| {
+            let end = *ends.get(&vreg).unwrap_or(&start);
+            Interval { vreg, start, end }
+        })
+        .collect();
+    intervals.sort_by_key(|iv| iv.start);
+    intervals
+}
+
+impl Default for Arm64CodeGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CodeGenerator for Arm64CodeGenerator {
+    fn target_name(&self) -> &'static str {
+        "aarch64"
+    }
+
+    /// ASTからAArch64マシンコードを生成
+    fn generate(&mut self, expr: &Expr) -> Result<CompiledFunction> {
+        self.code.clear();
+        self.variables.clear();
+        self.stack_offset = 0;
+
+        self.emit_prologue();
+        self.compile_expr(expr)?;
+        self.emit_epilogue();
+
+        Ok(CompiledFunction {
+            code: self.code.clone(),
+            entry_point: 0,
+            variables: self.variables.clone(),
+            function_table: HashMap::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn words(code: &[u8]) -> Vec<u32> {
+        code.chunks_exact(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect()
+    }
+
+    #[test]
+    fn test_simple_number_generation() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let result = codegen.generate(&Expr::Number(42)).unwrap();
+
+        assert!(!result.code.is_empty());
+        assert_eq!(result.code.len() % 4, 0); // 固定長命令なので常に4の倍数バイト
+        assert!(words(&result.code).contains(&0xd65f03c0)); // ret
+    }
+
+    #[test]
+    fn test_bool_literal_generation() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let result = codegen.generate(&Expr::Bool(false)).unwrap();
+
+        assert!(!result.code.is_empty());
+        assert_eq!(result.code.len() % 4, 0);
+        assert!(words(&result.code).contains(&0xd65f03c0)); // ret
+    }
+
+    #[test]
+    fn test_binary_expression_generation() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let mut parser = Parser::new("1 + 2").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(!result.code.is_empty());
+        assert_eq!(result.code.len() % 4, 0);
+    }
+
+    #[test]
+    fn test_division_and_modulo_use_sdiv() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let mut parser = Parser::new("7 / 2").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        // sdivのエンコーディングは常に0x9ac0_0c00形式のビットパターンを持つ
+        assert!(words(&result.code).iter().any(|w| (w & 0xffe0fc00) == 0x9ac00c00));
+    }
+
+    #[test]
+    fn test_variable_assignment_generation() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let mut parser = Parser::new("x = 42").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(!result.code.is_empty());
+        assert!(result.variables.contains_key("x"));
+    }
+
+    #[test]
+    fn test_if_expression_generation_patches_jumps() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let mut parser = Parser::new("if(1, 2, 3)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        let ws = words(&result.code);
+        // cbz (上位ビットパターン 1_0110100) とb (上位6ビット 000101) の両方が含まれるはず
+        assert!(ws.iter().any(|w| (w >> 24) == 0b1_0110100));
+        assert!(ws.iter().any(|w| (w >> 26) == 0b000101));
+    }
+
+    #[test]
+    fn test_while_loop_generation_has_backward_branch() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let mut parser = Parser::new("while(i < 5, i = i + 1)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        let ws = words(&result.code);
+        assert!(ws.iter().any(|w| (w >> 24) == 0b1_0110100)); // cbz (exit)
+
+        // 後方分岐(b)の相対ワードオフセットは負でなければならない
+        let b_pos = result.code.chunks_exact(4).position(|c| {
+            let w = u32::from_le_bytes(c.try_into().unwrap());
+            (w >> 26) == 0b000101
+        }).unwrap();
+        let b_word = u32::from_le_bytes(result.code[b_pos * 4..b_pos * 4 + 4].try_into().unwrap());
+        let imm26 = (b_word & 0x3ffffff) as i32;
+        // 26ビット符号付きなので符号拡張する
+        let signed = (imm26 << 6) >> 6;
+        assert!(signed < 0);
+    }
+
+    #[test]
+    fn test_while_with_sequence_body_generation() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let mut parser = Parser::new("while(i < 5, i = i + 1; sum = sum + i)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(!result.code.is_empty());
+        assert!(result.variables.contains_key("i"));
+        assert!(result.variables.contains_key("sum"));
+    }
+
+    #[test]
+    fn test_function_call_is_unsupported() {
+        let mut codegen = Arm64CodeGenerator::new();
+        let mut parser = Parser::new("fib(5)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        assert!(codegen.generate(&expr).is_err());
+    }
+
+    #[test]
+    fn test_target_name_is_aarch64() {
+        let codegen = Arm64CodeGenerator::new();
+        assert_eq!(codegen.target_name(), "aarch64");
+    }
+
+    #[test]
+    fn test_sibling_nested_if_operand_spills_across_the_nested_call() {
+        // `1 + if(1, 2, 3)`。左オペランドの`1`を物理レジスタに置いたまま右の`if`を
+        // コンパイルすると、分岐先でX1-X4の割り当てプールを使い潰してしまう。左オペランド
+        // は`allocate_registers`のクロバーポイント判定でスタックへスピルされ、フレーム
+        // ポインタ相対の`stur`（ストア）が生成されるはず。リテラルのみのこの式には
+        // 他に変数もスピルも存在しないため、スピルがなければこのstur自体現れない
+        let mut codegen = Arm64CodeGenerator::new();
+        let mut parser = Parser::new("1 + if(1, 2, 3)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        let ws = words(&result.code);
+        let stur_to_fp_count = ws
+            .iter()
+            .filter(|&&w| {
+                (w & 0xff000000) == 0xf8000000
+                    && (w & 0x00c00000) == 0 // opc=00 (ストア)
+                    && ((w >> 5) & 0x1f) == Register::Fp as u32
+            })
+            .count();
+        assert!(stur_to_fp_count >= 1);
+    }
+}
@@ -0,0 +1,38 @@
+// コード生成バックエンド群
+
+mod arm64;
+mod x86;
+
+pub use arm64::Arm64CodeGenerator;
+pub use x86::{Register, X86CodeGenerator};
+
+use crate::ast::Expr;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// 生成されたマシンコード。どのバックエンドが生成したかによらず同じ形で表現する
+#[derive(Debug, Clone)]
+pub struct CompiledFunction {
+    pub code: Vec<u8>,
+    pub entry_point: usize,
+    pub variables: HashMap<String, i32>, // 変数名 -> フレームポインタ相対オフセット
+    /// 関数名 -> `code`内でのエントリポイント（バイトオフセット）。再帰・相互呼び出しを
+    /// JITするバックエンド（x86-64）が、呼び出し先の本体も同じコード領域に埋め込んで
+    /// 使う。呼び出しをまだサポートしないバックエンドでは常に空
+    pub function_table: HashMap<String, usize>,
+}
+
+/// コード生成バックエンドが実装すべき共通インタフェース。`JitCompiler`はこのトレイトを
+/// 介してバックエンドを切り替えられるため、同じASTパイプラインから異なるISA
+/// （可変長命令のx86-64、32ビット固定長命令のAArch64など）向けのマシンコードを生成できる。
+///
+/// プロローグ/即値ロード/二項演算/分岐/ジャンプパッチといった命令エミットは、レジスタの
+/// 種類や命令の形がISAごとに大きく異なるため各バックエンドの内部実装（プライベートメソッド）
+/// に留め、トレイトの境界としては`generate`だけを公開する。
+pub trait CodeGenerator: Send {
+    /// このバックエンドが対象とするISAの名前（ログ・統計表示用）
+    fn target_name(&self) -> &'static str;
+
+    /// ASTからこのバックエンド向けのマシンコードを生成する
+    fn generate(&mut self, expr: &Expr) -> Result<CompiledFunction>;
+}
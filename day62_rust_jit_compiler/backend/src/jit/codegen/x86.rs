@@ -0,0 +1,1109 @@
+// x86-64 コード生成器
+
+use super::{CodeGenerator, CompiledFunction};
+use crate::ast::{BinaryOp, Expr};
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// x86-64の汎用レジスタ。ModRMバイトのreg/rmフィールドと同じ3ビットエンコーディングを使う
+/// （0=RAX, 1=RCX, ... 7=RDI）ため、`as usize` でレジスタファイルの添字に使える。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Register {
+    Rax = 0,
+    Rcx = 1,
+    Rdx = 2,
+    Rbx = 3,
+    Rsp = 4,
+    Rbp = 5,
+    Rsi = 6,
+    Rdi = 7,
+}
+
+impl Register {
+    /// ModRMのreg/rmフィールド（下位3ビット）からレジスタを求める
+    pub fn from_bits(bits: u8) -> Self {
+        match bits & 0x7 {
+            0 => Register::Rax,
+            1 => Register::Rcx,
+            2 => Register::Rdx,
+            3 => Register::Rbx,
+            4 => Register::Rsp,
+            5 => Register::Rbp,
+            6 => Register::Rsi,
+            _ => Register::Rdi,
+        }
+    }
+}
+
+fn modrm_byte(mode: u8, reg_bits: u8, rm: Register) -> u8 {
+    (mode << 6) | ((reg_bits & 0x7) << 3) | (rm as u8)
+}
+
+/// 算術式を仮想レジスタで表したIR命令。`X86CodeGenerator::lower_arithmetic`が
+/// `Expr`から生成し、線形スキャンでレジスタ割り当てした後に`emit_ir_instr`で命令を出す。
+#[derive(Debug, Clone)]
+enum IrInstr {
+    LoadImm { dest: VReg, value: i64 },
+    LoadVar { dest: VReg, name: String },
+    BinOp { dest: VReg, op: BinaryOp, lhs: VReg, rhs: VReg },
+    /// If式など算術IRの外で扱う必要がある部分式。`compile_expr`に委譲して結果をRAXで受け取る
+    Nested { dest: VReg, expr: Box<Expr> },
+}
+
+type VReg = usize;
+
+/// 割り当て結果: 物理レジスタか、rbp相対スタックスロットか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Location {
+    Reg(Register),
+    Stack(i32),
+}
+
+/// 仮想レジスタの生存区間（開始=定義位置, 終了=最後に使われた位置）
+#[derive(Debug, Clone, Copy)]
+struct Interval {
+    vreg: VReg,
+    start: usize,
+    end: usize,
+}
+
+/// System V AMD64呼び出し規約における整数引数レジスタ。このバックエンドはR8/R9
+/// （REX.B拡張が必要なレジスタ）をまだ符号化できないため、先頭4つ（RDI, RSI, RDX, RCX）
+/// までのレジスタ渡し引数のみ対応する
+const ARG_REGISTERS: [Register; 4] = [Register::Rdi, Register::Rsi, Register::Rdx, Register::Rcx];
+
+/// x86-64マシンコード生成器
+///
+/// 線形スキャンレジスタ割り当て方式: 算術式（数値・変数・二項演算）をまず仮想レジスタを
+/// 使ったフラットなIR命令列へ下げ、生存区間を1回の走査で求めたうえでRDX/RBX/RSI/RDIの
+/// 空きレジスタに割り当てる。レジスタが尽きた場合のみ`rbp`相対スタックスロットへスピル
+/// する。RAX/RCXは算術演算の一時置き場（スピルの読み書きや除算・比較の結果）として予約し、
+/// 割り当て対象には含めない。結果は常にRAXに残る。
+pub struct X86CodeGenerator {
+    code: Vec<u8>,
+    variables: HashMap<String, i32>,
+    stack_offset: i32,
+    next_vreg: VReg,
+    /// 関数名 -> `code`内でのエントリポイント（バイトオフセット）。
+    /// `fib`/`fact`/`pow`はインタープリタではネイティブのRustメソッドとして実装されて
+    /// いるが、JIT化にあたっては同じ計算をこのバックエンド自身のExpr ASTとして表現し
+    /// 直し、このコード領域の中に実体を埋め込む（`compile_builtin_function`）
+    function_table: HashMap<String, usize>,
+    /// `call rel32`のプレースホルダー位置と呼び出し先の関数名。すべての関数本体の
+    /// 埋め込みが終わった後、`function_table`を引いてまとめて解決する
+    relocations: Vec<(usize, String)>,
+    /// 関数の先頭からの「push - pop」の差分。`call`直前でrspが16バイト境界にあるかを
+    /// 判定するために使う（プロローグの`push rbp`で1になり、以後は対称なpush/pop
+    /// しか発行しないため通常は変化しない）
+    stack_parity: i32,
+}
+
+impl X86CodeGenerator {
+    pub fn new() -> Self {
+        Self {
+            code: Vec::new(),
+            variables: HashMap::new(),
+            stack_offset: 0,
+            next_vreg: 0,
+            function_table: HashMap::new(),
+            relocations: Vec::new(),
+            stack_parity: 0,
+        }
+    }
+
+    /// 式をコンパイル（結果はRAXに格納）
+    fn compile_expr(&mut self, expr: &Expr) -> Result<()> {
+        match expr {
+            Expr::Number(_) | Expr::Bool(_) | Expr::Variable(_) | Expr::Binary { .. } => {
+                self.compile_arithmetic(expr)
+            }
+
+            Expr::Assignment { name, value } => {
+                self.compile_expr(value)?;
+                let offset = self.allocate_variable(name.clone());
+                self.emit_mov_mem_reg(offset, Register::Rax);
+                Ok(())
+            }
+
+            Expr::If { condition, true_expr, false_expr } => {
+                self.compile_expr(condition)?;
+                self.emit_test_rax();
+
+                let false_jump_pos = self.emit_jz_placeholder();
+
+                self.compile_expr(true_expr)?;
+                let end_jump_pos = self.emit_jmp_placeholder();
+
+                let false_label_pos = self.code.len();
+                self.patch_jump(false_jump_pos, false_label_pos);
+
+                self.compile_expr(false_expr)?;
+
+                let end_label_pos = self.code.len();
+                self.patch_jump(end_jump_pos, end_label_pos);
+                Ok(())
+            }
+
+            Expr::While { condition, body } => {
+                // ループが一度も実行されなかった場合は0を返す。バックエッジで条件式を
+                // 再評価するとRAXが条件の値で上書きされてしまうため、直近のbody評価値は
+                // 専用のスピルスロットに退避しておき、ループを抜けた後にRAXへ復元する
+                let result_slot = self.allocate_spill_slot();
+                self.emit_mov_reg_imm(Register::Rax, 0);
+                self.emit_mov_mem_reg(result_slot, Register::Rax);
+
+                let loop_start = self.code.len();
+                self.compile_expr(condition)?;
+                self.emit_test_rax();
+                let exit_jump_pos = self.emit_jz_placeholder();
+
+                self.compile_expr(body)?;
+                self.emit_mov_mem_reg(result_slot, Register::Rax);
+
+                let back_jump_pos = self.emit_jmp_placeholder();
+                self.patch_jump(back_jump_pos, loop_start);
+
+                let exit_pos = self.code.len();
+                self.patch_jump(exit_jump_pos, exit_pos);
+
+                self.emit_mov_reg_mem(Register::Rax, result_slot);
+                Ok(())
+            }
+
+            Expr::Sequence(statements) => {
+                for statement in statements {
+                    self.compile_expr(statement)?;
+                }
+                Ok(())
+            }
+
+            Expr::FunctionCall { name, args } => self.compile_call(name, args),
+
+            // 論理演算子・浮動小数点・文字列はPhase 3では未対応
+            Expr::Logical { .. } => Err(anyhow!("Logical operators are unsupported in Phase 3")),
+            Expr::Unary { .. } => Err(anyhow!("Unary operators are unsupported in Phase 3")),
+            Expr::Float(_) => Err(anyhow!("Floating-point literals are unsupported in Phase 3")),
+            Expr::Str(_) => Err(anyhow!("String literals are unsupported in Phase 3")),
+            // `JitCompiler::resolve_fallbacks`がコード生成前に`Fallback`をどちらかの
+            // 枝へ解決し尽くしているはずなので、ここに到達するのはそのパスを経由しない
+            // 呼び出し元の不具合のみ
+            Expr::Fallback { .. } => Err(anyhow!("Fallback should have been resolved before codegen")),
+        }
+    }
+
+    /// 関数呼び出しをSystem V AMD64呼び出し規約でコンパイルする。呼び出し先の本体が
+    /// まだこのコード領域に埋め込まれていなければ先にコンパイルし（`fib`の自己再帰の
+    /// ように、呼び出し先が自分自身の場合は`function_table`への事前登録により即座に
+    /// 解決される）、引数をレジスタへ積んでから`call rel32`を発行する
+    fn compile_call(&mut self, name: &str, args: &[Expr]) -> Result<()> {
+        if args.len() > ARG_REGISTERS.len() {
+            return Err(anyhow!(
+                "Function calls with more than {} arguments are unsupported in Phase 3 (no r8/r9 encoding yet)",
+                ARG_REGISTERS.len()
+            ));
+        }
+
+        if !self.function_table.contains_key(name) {
+            self.compile_builtin_function(name)?;
+        }
+
+        // 引数は左から順に評価し、その都度RAXから対応する引数レジスタへ移す。
+        // 呼び出し先の内部ではRDI/RSI/RBX/RDXを自由に使うため、この時点でRDI等へ
+        // 積んだ値は呼び出し後は保存されない前提（このバックエンドはまだ呼び出しを
+        // またぐレジスタの退避を行わない。If/Whileの入れ子と同じ既知の制約である）
+        for (i, arg) in args.iter().enumerate() {
+            self.compile_expr(arg)?;
+            self.emit_mov_reg_reg(ARG_REGISTERS[i], Register::Rax);
+        }
+
+        // callの直前でrspが16バイト境界になるよう調整する。このバックエンドは
+        // プロローグで`push rbp`を1回行うだけで、それ以外に不揃いなpush/popを残さない
+        // 規約なので通常は既に揃っているが、将来の変更に備えてパリティを明示的に
+        // 追跡しておく
+        let needs_pad = self.stack_parity % 2 == 0;
+        if needs_pad {
+            self.emit_push_pad();
+        }
+
+        let call_pos = self.emit_call_placeholder();
+        self.relocations.push((call_pos, name.to_string()));
+
+        if needs_pad {
+            self.emit_pop_pad();
+        }
+
+        // 結果はcallから戻った時点でRAXに入っている
+        Ok(())
+    }
+
+    /// 組み込み関数（`fib`/`fact`/`pow`）の本体をこのコード領域に埋め込む。
+    /// インタープリタ側ではこれらはネイティブのRustメソッドだが、JIT化にあたっては
+    /// 同じ計算をこのコンパイラ自身のExpr ASTとして表現し直し、通常の式と同じ
+    /// パイプラインでコンパイルする
+    fn compile_builtin_function(&mut self, name: &str) -> Result<()> {
+        let (param_names, body): (&[&str], Expr) = match name {
+            "fib" => (&["n"], Self::fib_body()),
+            "fact" => (&["n"], Self::fact_body()),
+            "pow" => (&["base", "exp"], Self::pow_body()),
+            _ => return Err(anyhow!("Unknown function for JIT compilation: {}", name)),
+        };
+
+        // 呼び出し位置の途中にこの関数本体を埋め込むため、実行が誤ってそのまま
+        // 本体へ「落ちて」こないよう、直後に本体をまたぐ無条件ジャンプを先に置く
+        let skip_jump_pos = self.emit_jmp_placeholder();
+
+        let entry = self.code.len();
+        // 自己再帰（`fib`が`fib`自身を呼ぶなど）が本体コンパイル中にここを引けるよう、
+        // 本体のコンパイルより先にエントリポイントを登録しておく
+        self.function_table.insert(name.to_string(), entry);
+
+        // 呼び出し元と変数名前空間を共有しないよう、関数ごとに独立したスコープにする。
+        // `stack_parity`も呼び出し元フレームのアライメント状態とは無関係な、
+        // この関数自身のフレームに閉じた値として0から数え直す
+        let saved_variables = std::mem::take(&mut self.variables);
+        let saved_stack_offset = self.stack_offset;
+        let saved_stack_parity = self.stack_parity;
+        self.variables = HashMap::new();
+        self.stack_offset = 0;
+        self.stack_parity = 0;
+
+        let sub_rsp_pos = self.emit_prologue();
+        for (i, pname) in param_names.iter().enumerate() {
+            let offset = self.allocate_variable((*pname).to_string());
+            self.emit_mov_mem_reg(offset, ARG_REGISTERS[i]);
+        }
+        self.compile_expr(&body)?;
+        self.patch_frame_size(sub_rsp_pos);
+        self.emit_epilogue();
+
+        self.variables = saved_variables;
+        self.stack_offset = saved_stack_offset;
+        self.stack_parity = saved_stack_parity;
+
+        let after_body = self.code.len();
+        self.patch_jump(skip_jump_pos, after_body);
+
+        Ok(())
+    }
+
+    /// `n <= 1 ? n : fib(n - 1) + fib(n - 2)`（`Interpreter::fibonacci`と同じ定義）
+    fn fib_body() -> Expr {
+        Expr::If {
+            condition: Box::new(Expr::Binary {
+                left: Box::new(Expr::Variable("n".to_string())),
+                op: BinaryOp::LessEq,
+                right: Box::new(Expr::Number(1)),
+            }),
+            true_expr: Box::new(Expr::Variable("n".to_string())),
+            false_expr: Box::new(Expr::Binary {
+                left: Box::new(Expr::FunctionCall {
+                    name: "fib".to_string(),
+                    args: vec![Expr::Binary {
+                        left: Box::new(Expr::Variable("n".to_string())),
+                        op: BinaryOp::Sub,
+                        right: Box::new(Expr::Number(1)),
+                    }],
+                }),
+                op: BinaryOp::Add,
+                right: Box::new(Expr::FunctionCall {
+                    name: "fib".to_string(),
+                    args: vec![Expr::Binary {
+                        left: Box::new(Expr::Variable("n".to_string())),
+                        op: BinaryOp::Sub,
+                        right: Box::new(Expr::Number(2)),
+                    }],
+                }),
+            }),
+        }
+    }
+
+    /// `n <= 1 ? 1 : n * fact(n - 1)`（`Interpreter::factorial`と同じ定義）
+    fn fact_body() -> Expr {
+        Expr::If {
+            condition: Box::new(Expr::Binary {
+                left: Box::new(Expr::Variable("n".to_string())),
+                op: BinaryOp::LessEq,
+                right: Box::new(Expr::Number(1)),
+            }),
+            true_expr: Box::new(Expr::Number(1)),
+            false_expr: Box::new(Expr::Binary {
+                left: Box::new(Expr::Variable("n".to_string())),
+                op: BinaryOp::Mul,
+                right: Box::new(Expr::FunctionCall {
+                    name: "fact".to_string(),
+                    args: vec![Expr::Binary {
+                        left: Box::new(Expr::Variable("n".to_string())),
+                        op: BinaryOp::Sub,
+                        right: Box::new(Expr::Number(1)),
+                    }],
+                }),
+            }),
+        }
+    }
+
+    /// `exp < 0 ? 0 : (exp == 0 ? 1 : base * pow(base, exp - 1))`
+    /// （`exp < 0`で0を返す分岐も含め`Interpreter::power`と同じ定義）
+    fn pow_body() -> Expr {
+        Expr::If {
+            condition: Box::new(Expr::Binary {
+                left: Box::new(Expr::Variable("exp".to_string())),
+                op: BinaryOp::Less,
+                right: Box::new(Expr::Number(0)),
+            }),
+            true_expr: Box::new(Expr::Number(0)),
+            false_expr: Box::new(Expr::If {
+                condition: Box::new(Expr::Binary {
+                    left: Box::new(Expr::Variable("exp".to_string())),
+                    op: BinaryOp::Equal,
+                    right: Box::new(Expr::Number(0)),
+                }),
+                true_expr: Box::new(Expr::Number(1)),
+                false_expr: Box::new(Expr::Binary {
+                    left: Box::new(Expr::Variable("base".to_string())),
+                    op: BinaryOp::Mul,
+                    right: Box::new(Expr::FunctionCall {
+                        name: "pow".to_string(),
+                        args: vec![
+                            Expr::Variable("base".to_string()),
+                            Expr::Binary {
+                                left: Box::new(Expr::Variable("exp".to_string())),
+                                op: BinaryOp::Sub,
+                                right: Box::new(Expr::Number(1)),
+                            },
+                        ],
+                    }),
+                }),
+            }),
+        }
+    }
+
+    /// 数値・変数・二項演算からなる算術式をIRへ下げ、レジスタ割り当てしてコンパイルする
+    fn compile_arithmetic(&mut self, expr: &Expr) -> Result<()> {
+        self.next_vreg = 0;
+        let mut instrs = Vec::new();
+        let root = self.lower_arithmetic(expr, &mut instrs)?;
+
+        let intervals = compute_intervals(&instrs, root);
+        let has_div_mod = instrs
+            .iter()
+            .any(|instr| matches!(instr, IrInstr::BinOp { op: BinaryOp::Div | BinaryOp::Mod, .. }));
+        // `Nested`（if/while/関数呼び出し）は`compile_expr`へ丸ごと委譲するため、中で
+        // 呼び出し規約に従いRDI/RSI/RBX/RDXを自由に使い潰す。このプール全体を割り当て
+        // 対象にしている以上、`Nested`をまたいで生存する区間は物理レジスタに置いたままに
+        // できない（兄弟の`fib(n-1)+fib(n-2)`のようなケースで前半の結果が呼び出しに
+        // よって破壊される）ため、ここを「クロバーポイント」として扱いスピルを強制する
+        let clobber_points: Vec<usize> = instrs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, instr)| matches!(instr, IrInstr::Nested { .. }).then_some(i))
+            .collect();
+        let locations = self.allocate_registers(&intervals, has_div_mod, &clobber_points);
+
+        for instr in &instrs {
+            self.emit_ir_instr(instr, &locations)?;
+        }
+
+        self.ensure_in_rax(locations[&root]);
+        Ok(())
+    }
+
+    /// ASTをフラットなIR命令列へ下げる（仮想レジスタを払い出しながら再帰的に処理）
+    fn lower_arithmetic(&mut self, expr: &Expr, instrs: &mut Vec<IrInstr>) -> Result<VReg> {
+        match expr {
+            Expr::Number(n) => {
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::LoadImm { dest, value: *n });
+                Ok(dest)
+            }
+
+            // 真偽値は0/1の整数として表現する（比較演算の結果と同じレジスタ上の形）
+            Expr::Bool(b) => {
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::LoadImm { dest, value: if *b { 1 } else { 0 } });
+                Ok(dest)
+            }
+
+            Expr::Variable(name) => {
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::LoadVar { dest, name: name.clone() });
+                Ok(dest)
+            }
+
+            Expr::Binary { left, op, right } => {
+                let lhs = self.lower_arithmetic(left, instrs)?;
+                let rhs = self.lower_arithmetic(right, instrs)?;
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::BinOp { dest, op: op.clone(), lhs, rhs });
+                Ok(dest)
+            }
+
+            // if(...)やwhile(...)、関数呼び出しは二項演算のオペランドにも現れ得る
+            // （例: `fib(n-1) + fib(n-2)`）。その場合はcompile_expr側の対応する分岐に
+            // そのまま委譲し、結果をRAX経由で受け取る
+            Expr::If { .. } | Expr::While { .. } | Expr::FunctionCall { .. } => {
+                let dest = self.alloc_vreg();
+                instrs.push(IrInstr::Nested { dest, expr: Box::new(expr.clone()) });
+                Ok(dest)
+            }
+
+            Expr::Assignment { .. } => {
+                Err(anyhow!("Assignment is unsupported as an arithmetic operand in Phase 3"))
+            }
+            Expr::Sequence(_) => {
+                Err(anyhow!("Sequence is unsupported as an arithmetic operand in Phase 3"))
+            }
+            Expr::Logical { .. } => Err(anyhow!("Logical operators are unsupported in Phase 3")),
+            Expr::Unary { .. } => Err(anyhow!("Unary operators are unsupported in Phase 3")),
+            Expr::Float(_) => Err(anyhow!("Floating-point literals are unsupported in Phase 3")),
+            Expr::Str(_) => Err(anyhow!("String literals are unsupported in Phase 3")),
+            // `JitCompiler::resolve_fallbacks`がコード生成前に`Fallback`をどちらかの
+            // 枝へ解決し尽くしているはずなので、ここに到達するのはそのパスを経由しない
+            // 呼び出し元の不具合のみ
+            Expr::Fallback { .. } => Err(anyhow!("Fallback should have been resolved before codegen")),
+        }
+    }
+
+    fn alloc_vreg(&mut self) -> VReg {
+        let vreg = self.next_vreg;
+        self.next_vreg += 1;
+        vreg
+    }
+
+    /// 線形スキャンでRDX/RBX/RSI/RDIを割り当てる。除算・剰余を含む式はRDXが
+    /// cqo/idivに使われるため割り当て対象から外す。`clobber_points`をまたいで生存する
+    /// 区間は、呼び出し先が割り当て対象レジスタを自由に使い潰すため物理レジスタに
+    /// 置かずスタックへスピルを強制する
+    fn allocate_registers(
+        &mut self,
+        intervals: &[Interval],
+        has_div_mod: bool,
+        clobber_points: &[usize],
+    ) -> HashMap<VReg, Location> {
+        let pool_order: &[Register] = if has_div_mod {
+            &[Register::Rdi, Register::Rsi, Register::Rbx]
+        } else {
+            &[Register::Rdi, Register::Rsi, Register::Rbx, Register::Rdx]
+        };
+        let mut free: Vec<Register> = pool_order.to_vec();
+        let mut active: Vec<(Interval, Register)> = Vec::new();
+        let mut assignment: HashMap<VReg, Location> = HashMap::new();
+
+        for interval in intervals {
+            // 区間の終わった仮想レジスタのレジスタを回収する
+            active.retain(|(iv, reg)| {
+                if iv.end < interval.start {
+                    free.push(*reg);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            let crosses_clobber_point = clobber_points
+                .iter()
+                .any(|&point| interval.start < point && point < interval.end);
+            if crosses_clobber_point {
+                let offset = self.allocate_spill_slot();
+                assignment.insert(interval.vreg, Location::Stack(offset));
+                continue;
+            }
+
+            if let Some(reg) = free.pop() {
+                assignment.insert(interval.vreg, Location::Reg(reg));
+                active.push((*interval, reg));
+                active.sort_by_key(|(iv, _)| iv.end);
+            } else if let Some(&(spill_candidate, spill_reg)) = active.last() {
+                if spill_candidate.end > interval.end {
+                    // もっとも終了が遅い区間をスピルし、空いたレジスタを今の区間に回す
+                    active.pop();
+                    let offset = self.allocate_spill_slot();
+                    assignment.insert(spill_candidate.vreg, Location::Stack(offset));
+                    assignment.insert(interval.vreg, Location::Reg(spill_reg));
+                    active.push((*interval, spill_reg));
+                    active.sort_by_key(|(iv, _)| iv.end);
+                } else {
+                    let offset = self.allocate_spill_slot();
+                    assignment.insert(interval.vreg, Location::Stack(offset));
+                }
+            } else {
+                let offset = self.allocate_spill_slot();
+                assignment.insert(interval.vreg, Location::Stack(offset));
+            }
+        }
+
+        assignment
+    }
+
+    fn emit_ir_instr(&mut self, instr: &IrInstr, locations: &HashMap<VReg, Location>) -> Result<()> {
+        match instr {
+            IrInstr::LoadImm { dest, value } => {
+                match locations[dest] {
+                    Location::Reg(r) => self.emit_mov_reg_imm(r, *value),
+                    Location::Stack(off) => {
+                        self.emit_mov_reg_imm(Register::Rax, *value);
+                        self.emit_mov_mem_reg(off, Register::Rax);
+                    }
+                }
+                Ok(())
+            }
+
+            IrInstr::LoadVar { dest, name } => {
+                // この式の中で一度も代入されていない変数は、直前のインタープリタ実行結果などから
+                // 値が供給される「外部変数」とみなし、その場でスタックスロットを割り当てる
+                // （実際の初期値は`ExecutableMemory`が実行前にシードする）
+                let var_offset = self.allocate_variable(name.clone());
+                match locations[dest] {
+                    Location::Reg(r) => self.emit_mov_reg_mem(r, var_offset),
+                    Location::Stack(off) => {
+                        self.emit_mov_reg_mem(Register::Rax, var_offset);
+                        self.emit_mov_mem_reg(off, Register::Rax);
+                    }
+                }
+                Ok(())
+            }
+
+            IrInstr::BinOp { dest, op, lhs, rhs } => {
+                let dest_loc = locations[dest];
+                let lhs_loc = locations[lhs];
+                let rhs_loc = locations[rhs];
+
+                match op {
+                    BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul => {
+                        let lhs_reg = self.materialize(lhs_loc, Register::Rax);
+                        let rhs_reg = self.materialize(rhs_loc, Register::Rcx);
+                        match op {
+                            BinaryOp::Add => self.emit_add(lhs_reg, rhs_reg),
+                            BinaryOp::Sub => self.emit_sub(lhs_reg, rhs_reg),
+                            BinaryOp::Mul => self.emit_imul(lhs_reg, rhs_reg),
+                            _ => unreachable!(),
+                        }
+                        self.move_result_to(dest_loc, lhs_reg);
+                    }
+
+                    BinaryOp::Div | BinaryOp::Mod => {
+                        let lhs_reg = self.materialize(lhs_loc, Register::Rax);
+                        if lhs_reg != Register::Rax {
+                            self.emit_mov_reg_reg(Register::Rax, lhs_reg);
+                        }
+                        let rhs_reg = self.materialize(rhs_loc, Register::Rcx);
+                        self.emit_cqo();
+                        self.emit_idiv(rhs_reg);
+                        let result_reg = if matches!(op, BinaryOp::Div) { Register::Rax } else { Register::Rdx };
+                        self.move_result_to(dest_loc, result_reg);
+                    }
+
+                    BinaryOp::Equal
+                    | BinaryOp::NotEqual
+                    | BinaryOp::Less
+                    | BinaryOp::Greater
+                    | BinaryOp::LessEq
+                    | BinaryOp::GreaterEq => {
+                        let lhs_reg = self.materialize(lhs_loc, Register::Rax);
+                        let rhs_reg = self.materialize(rhs_loc, Register::Rcx);
+                        self.emit_cmp(lhs_reg, rhs_reg);
+                        let cond = match op {
+                            BinaryOp::Equal => SetCond::Equal,
+                            BinaryOp::NotEqual => SetCond::NotEqual,
+                            BinaryOp::Less => SetCond::Less,
+                            BinaryOp::Greater => SetCond::Greater,
+                            BinaryOp::LessEq => SetCond::LessEq,
+                            BinaryOp::GreaterEq => SetCond::GreaterEq,
+                            _ => unreachable!(),
+                        };
+                        self.emit_setcc(cond);
+                        self.move_result_to(dest_loc, Register::Rax);
+                    }
+                }
+                Ok(())
+            }
+
+            IrInstr::Nested { dest, expr } => {
+                self.compile_expr(expr)?;
+                self.move_result_to(locations[dest], Register::Rax);
+                Ok(())
+            }
+        }
+    }
+
+    /// オペランドの値をレジスタとして取得する。既にレジスタにあればそのまま、
+    /// スタックにスピルされていれば`scratch`へロードして返す
+    fn materialize(&mut self, loc: Location, scratch: Register) -> Register {
+        match loc {
+            Location::Reg(r) => r,
+            Location::Stack(off) => {
+                self.emit_mov_reg_mem(scratch, off);
+                scratch
+            }
+        }
+    }
+
+    /// 計算結果（`result_reg`にある値）を最終的な置き場所へ移す
+    fn move_result_to(&mut self, dest: Location, result_reg: Register) {
+        match dest {
+            Location::Reg(r) if r != result_reg => self.emit_mov_reg_reg(r, result_reg),
+            Location::Reg(_) => {}
+            Location::Stack(off) => self.emit_mov_mem_reg(off, result_reg),
+        }
+    }
+
+    /// 式全体の最終結果をRAXへ集約する（compile_expr側の「結果は常にRAX」という契約を保つ）
+    fn ensure_in_rax(&mut self, loc: Location) {
+        match loc {
+            Location::Reg(Register::Rax) => {}
+            Location::Reg(r) => self.emit_mov_reg_reg(Register::Rax, r),
+            Location::Stack(off) => self.emit_mov_reg_mem(Register::Rax, off),
+        }
+    }
+
+    /// 変数用のスタック領域を確保
+    fn allocate_variable(&mut self, name: String) -> i32 {
+        if let Some(&offset) = self.variables.get(&name) {
+            offset
+        } else {
+            self.stack_offset -= 8; // 8バイト（64ビット）
+            self.variables.insert(name, self.stack_offset);
+            self.stack_offset
+        }
+    }
+
+    /// レジスタ割り当てで溢れた仮想レジスタ用のスピルスロットを確保する。
+    /// `allocate_variable`と同じオフセットカウンタを共有するため名前付き変数と衝突しない
+    fn allocate_spill_slot(&mut self) -> i32 {
+        self.stack_offset -= 8;
+        self.stack_offset
+    }
+
+    // === x86-64命令エミット関数 ===
+
+    /// 関数プロローグ。ローカル変数・スピルスロット用の`sub rsp, imm32`を続けて
+    /// 発行するが、その時点ではまだ本体をコンパイルしていないためフレームサイズが
+    /// 確定しない。ここではプレースホルダーとして0を仮置きし、本体コンパイル後に
+    /// `patch_frame_size`で実際のバイト数へ書き換える（`patch_jump`と同じ二段階方式）。
+    /// これを省くと、本体の`call`/パディングpushが積むスタックが`[rbp-8]`以下の
+    /// ローカル領域を素通りして上書きしてしまう
+    fn emit_prologue(&mut self) -> usize {
+        self.code.push(0x55); // push rbp
+        self.code.extend_from_slice(&[0x48, 0x89, 0xe5]); // mov rbp, rsp
+        self.stack_parity += 1;
+
+        self.code.extend_from_slice(&[0x48, 0x81, 0xec]); // sub rsp, imm32 (プレースホルダー)
+        let pos = self.code.len();
+        self.code.extend_from_slice(&[0, 0, 0, 0]);
+        pos
+    }
+
+    /// `emit_prologue`が仮置きした`sub rsp, imm32`のプレースホルダーを、本体コンパイル後に
+    /// 確定した実際のフレームサイズで書き換える
+    fn patch_frame_size(&mut self, sub_rsp_pos: usize) {
+        let frame_size = (-self.stack_offset) as u32;
+        self.code[sub_rsp_pos..sub_rsp_pos + 4].copy_from_slice(&frame_size.to_le_bytes());
+    }
+
+    /// 関数エピローグ
+    fn emit_epilogue(&mut self) {
+        self.code.extend_from_slice(&[0x48, 0x89, 0xec]); // mov rsp, rbp
+        self.code.push(0x5d); // pop rbp
+        self.code.push(0xc3); // ret
+        self.stack_parity -= 1;
+    }
+
+    /// call直前のアライメント調整用パディング。popされるだけで値は使われないため、
+    /// すでにエミュレータが対応している`push rax`を流用する
+    fn emit_push_pad(&mut self) {
+        self.code.push(0x50); // push rax
+        self.stack_parity += 1;
+    }
+
+    /// `emit_push_pad`で積んだパディングを捨てる。RAXは呼び出し結果を保持しているため
+    /// 上書きしないよう、呼び出し元で不要なRCXへpopする
+    fn emit_pop_pad(&mut self) {
+        self.code.push(0x59); // pop rcx
+        self.stack_parity -= 1;
+    }
+
+    /// call rel32 (プレースホルダー) - パッチ対象のオフセット位置を返す
+    fn emit_call_placeholder(&mut self) -> usize {
+        self.code.push(0xe8);
+        let pos = self.code.len();
+        self.code.extend_from_slice(&[0, 0, 0, 0]);
+        pos
+    }
+
+    /// mov dest, immediate
+    fn emit_mov_reg_imm(&mut self, dest: Register, value: i64) {
+        if (i32::MIN as i64..=i32::MAX as i64).contains(&value) {
+            // mov r32, imm32 (上位32ビットは0クリアされる)
+            self.code.push(0xb8 + dest as u8);
+            self.code.extend_from_slice(&(value as i32).to_le_bytes());
+        } else {
+            // mov r64, imm64
+            self.code.push(0x48);
+            self.code.push(0xb8 + dest as u8);
+            self.code.extend_from_slice(&(value as u64).to_le_bytes());
+        }
+    }
+
+    /// mov dest, src
+    fn emit_mov_reg_reg(&mut self, dest: Register, src: Register) {
+        self.code.extend_from_slice(&[0x48, 0x89, modrm_byte(0b11, src as u8, dest)]);
+    }
+
+    /// add dest, src
+    fn emit_add(&mut self, dest: Register, src: Register) {
+        self.code.extend_from_slice(&[0x48, 0x01, modrm_byte(0b11, src as u8, dest)]);
+    }
+
+    /// sub dest, src
+    fn emit_sub(&mut self, dest: Register, src: Register) {
+        self.code.extend_from_slice(&[0x48, 0x29, modrm_byte(0b11, src as u8, dest)]);
+    }
+
+    /// imul dest, src (符号付き乗算)
+    fn emit_imul(&mut self, dest: Register, src: Register) {
+        self.code.extend_from_slice(&[0x48, 0x0f, 0xaf, modrm_byte(0b11, dest as u8, src)]);
+    }
+
+    /// cqo (RAXの符号をRDX:RAXへ拡張)
+    fn emit_cqo(&mut self) {
+        self.code.extend_from_slice(&[0x48, 0x99]);
+    }
+
+    /// idiv divisor。RAX:RDXを被除数として符号付き除算し、商はRAX・剰余はRDXに残る
+    fn emit_idiv(&mut self, divisor: Register) {
+        self.code.extend_from_slice(&[0x48, 0xf7, modrm_byte(0b11, 7, divisor)]);
+    }
+
+    /// test rax, rax
+    fn emit_test_rax(&mut self) {
+        self.code.extend_from_slice(&[0x48, 0x85, 0xc0]);
+    }
+
+    /// cmp lhs, rhs
+    fn emit_cmp(&mut self, lhs: Register, rhs: Register) {
+        self.code.extend_from_slice(&[0x48, 0x39, modrm_byte(0b11, rhs as u8, lhs)]);
+    }
+
+    /// set<cc> al; movzx rax, al（直前のcmp/testの結果をもとにRAXへ0/1を書き込む）
+    fn emit_setcc(&mut self, cond: SetCond) {
+        self.code.extend_from_slice(&[0x0f, cond.opcode(), 0xc0]); // set<cc> al
+        self.code.extend_from_slice(&[0x48, 0x0f, 0xb6, 0xc0]); // movzx rax, al
+    }
+
+    /// mov [rbp + offset], src
+    fn emit_mov_mem_reg(&mut self, offset: i32, src: Register) {
+        if (-128..=127).contains(&offset) {
+            self.code.extend_from_slice(&[0x48, 0x89, modrm_byte(0b01, src as u8, Register::Rbp)]);
+            self.code.push(offset as i8 as u8);
+        } else {
+            self.code.extend_from_slice(&[0x48, 0x89, modrm_byte(0b10, src as u8, Register::Rbp)]);
+            self.code.extend_from_slice(&offset.to_le_bytes());
+        }
+    }
+
+    /// mov dest, [rbp + offset]
+    fn emit_mov_reg_mem(&mut self, dest: Register, offset: i32) {
+        if (-128..=127).contains(&offset) {
+            self.code.extend_from_slice(&[0x48, 0x8b, modrm_byte(0b01, dest as u8, Register::Rbp)]);
+            self.code.push(offset as i8 as u8);
+        } else {
+            self.code.extend_from_slice(&[0x48, 0x8b, modrm_byte(0b10, dest as u8, Register::Rbp)]);
+            self.code.extend_from_slice(&offset.to_le_bytes());
+        }
+    }
+
+    /// jz rel32 (プレースホルダー) - パッチ対象のオフセット位置を返す
+    fn emit_jz_placeholder(&mut self) -> usize {
+        self.code.extend_from_slice(&[0x0f, 0x84]);
+        let pos = self.code.len();
+        self.code.extend_from_slice(&[0, 0, 0, 0]);
+        pos
+    }
+
+    /// jmp rel32 (プレースホルダー) - パッチ対象のオフセット位置を返す
+    fn emit_jmp_placeholder(&mut self) -> usize {
+        self.code.push(0xe9);
+        let pos = self.code.len();
+        self.code.extend_from_slice(&[0, 0, 0, 0]);
+        pos
+    }
+
+    /// ジャンプ先アドレスをパッチ（相対オフセットはジャンプ命令直後から数える）
+    fn patch_jump(&mut self, jump_pos: usize, target_pos: usize) {
+        let offset = (target_pos as i32) - (jump_pos as i32) - 4;
+        self.code[jump_pos..jump_pos + 4].copy_from_slice(&offset.to_le_bytes());
+    }
+}
+
+/// IR命令列から各仮想レジスタの生存区間（開始=定義位置、終了=最後の使用位置）を求める。
+/// `root`（式全体の最終結果）は最後まで生存しているものとして扱う
+fn compute_intervals(instrs: &[IrInstr], root: VReg) -> Vec<Interval> {
+    let mut starts: HashMap<VReg, usize> = HashMap::new();
+    let mut ends: HashMap<VReg, usize> = HashMap::new();
+
+    for (i, instr) in instrs.iter().enumerate() {
+        match instr {
+            IrInstr::LoadImm { dest, .. } | IrInstr::LoadVar { dest, .. } | IrInstr::Nested { dest, .. } => {
+                starts.entry(*dest).or_insert(i);
+            }
+            IrInstr::BinOp { dest, lhs, rhs, .. } => {
+                starts.entry(*dest).or_insert(i);
+                ends.insert(*lhs, i);
+                ends.insert(*rhs, i);
+            }
+        }
+    }
+    ends.insert(root, instrs.len());
+
+    let mut intervals: Vec<Interval> = starts
+        .into_iter()
+        .map(|(vreg, start)| {
+            let end = *ends.get(&vreg).unwrap_or(&start);
+            Interval { vreg, start, end }
+        })
+        .collect();
+    intervals.sort_by_key(|iv| iv.start);
+    intervals
+}
+
+/// cmp直後のset<cc>命令の種類
+enum SetCond {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessEq,
+    GreaterEq,
+}
+
+impl SetCond {
+    fn opcode(&self) -> u8 {
+        match self {
+            SetCond::Equal => 0x94,     // sete
+            SetCond::NotEqual => 0x95,  // setne
+            SetCond::Less => 0x9c,      // setl
+            SetCond::Greater => 0x9f,   // setg
+            SetCond::LessEq => 0x9e,    // setle
+            SetCond::GreaterEq => 0x9d, // setge
+        }
+    }
+}
+
+impl Default for X86CodeGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CodeGenerator for X86CodeGenerator {
+    fn target_name(&self) -> &'static str {
+        "x86-64"
+    }
+
+    /// ASTからx86-64マシンコードを生成
+    fn generate(&mut self, expr: &Expr) -> Result<CompiledFunction> {
+        self.code.clear();
+        self.variables.clear();
+        self.stack_offset = 0;
+        self.function_table.clear();
+        self.relocations.clear();
+        self.stack_parity = 0;
+
+        let sub_rsp_pos = self.emit_prologue();
+        self.compile_expr(expr)?;
+        self.patch_frame_size(sub_rsp_pos);
+        self.emit_epilogue();
+
+        // 呼び出し先の関数本体はすべて同じコード領域に埋め込まれているため、
+        // 収集しておいた`call rel32`のプレースホルダーをここでまとめて解決する
+        for (call_pos, name) in std::mem::take(&mut self.relocations) {
+            let target = *self
+                .function_table
+                .get(&name)
+                .ok_or_else(|| anyhow!("Undefined function reference: {}", name))?;
+            self.patch_jump(call_pos, target);
+        }
+
+        Ok(CompiledFunction {
+            code: self.code.clone(),
+            entry_point: 0,
+            variables: self.variables.clone(),
+            function_table: self.function_table.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_simple_number_generation() {
+        let mut codegen = X86CodeGenerator::new();
+        let result = codegen.generate(&Expr::Number(42)).unwrap();
+
+        assert!(!result.code.is_empty());
+        assert!(result.code.contains(&0x55)); // push rbp
+        assert!(result.code.contains(&0xc3)); // ret
+    }
+
+    #[test]
+    fn test_bool_literal_generation() {
+        let mut codegen = X86CodeGenerator::new();
+        let result = codegen.generate(&Expr::Bool(true)).unwrap();
+
+        assert!(!result.code.is_empty());
+        assert!(result.code.contains(&0xc3)); // ret
+    }
+
+    #[test]
+    fn test_binary_expression_generation() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("1 + 2").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(!result.code.is_empty());
+    }
+
+    #[test]
+    fn test_division_and_modulo_use_idiv() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("7 / 2").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        // cqo (48 99) は符号付き除算の前処理として必ず現れる
+        assert!(result.code.windows(2).any(|w| w == [0x48, 0x99]));
+    }
+
+    #[test]
+    fn test_variable_assignment_generation() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("x = 42").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(!result.code.is_empty());
+        assert!(result.variables.contains_key("x"));
+    }
+
+    #[test]
+    fn test_if_expression_generation_patches_jumps() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("if(1, 2, 3)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        // jz (0f 84) と jmp (e9) の両方が含まれるはず
+        assert!(result.code.windows(2).any(|w| w == [0x0f, 0x84]));
+        assert!(result.code.contains(&0xe9));
+    }
+
+    #[test]
+    fn test_function_call_compiles_and_registers_entry_point() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("fib(5)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(result.function_table.contains_key("fib"));
+        // call rel32 (e8) が呼び出し先へのリロケーションとして含まれるはず
+        assert!(result.code.contains(&0xe8));
+    }
+
+    #[test]
+    fn test_unknown_function_call_is_an_error() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("frobnicate(5)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        assert!(codegen.generate(&expr).is_err());
+    }
+
+    #[test]
+    fn test_function_call_with_too_many_arguments_is_an_error() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("pow(1, 2, 3, 4, 5)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        assert!(codegen.generate(&expr).is_err());
+    }
+
+    #[test]
+    fn test_deep_arithmetic_expression_keeps_temporaries_in_registers() {
+        // ((1+2)*(3-4))+(5*6) は同時に生存する一時値が複数あるため、
+        // レジスタ割り当て（必要であればスピルも含め）が正しく行われることを確認する
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("((1+2)*(3-4))+(5*6)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(!result.code.is_empty());
+        // push/popを使ったスタックマシン方式の名残りが残っていないこと
+        assert!(!result.code.contains(&0x50)); // push rax
+        assert!(!result.code.contains(&0x59)); // pop rcx
+    }
+
+    #[test]
+    fn test_while_loop_generation_has_backward_jump() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("while(i < 5, i = i + 1)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        // jz (exit) と jmp (backward, loop_startへ戻る) の両方が含まれるはず
+        assert!(result.code.windows(2).any(|w| w == [0x0f, 0x84]));
+        assert!(result.code.contains(&0xe9));
+
+        // jmpの相対オフセットは負（後方ジャンプ）でなければならない
+        let jmp_pos = result.code.iter().position(|&b| b == 0xe9).unwrap();
+        let rel = i32::from_le_bytes(result.code[jmp_pos + 1..jmp_pos + 5].try_into().unwrap());
+        assert!(rel < 0);
+    }
+
+    #[test]
+    fn test_while_with_sequence_body_generation() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("while(i < 5, i = i + 1; sum = sum + i)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(!result.code.is_empty());
+        assert!(result.variables.contains_key("i"));
+        assert!(result.variables.contains_key("sum"));
+    }
+
+    #[test]
+    fn test_if_nested_inside_binary_expression() {
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("1 + if(1, 2, 3)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        assert!(!result.code.is_empty());
+        assert!(result.code.windows(2).any(|w| w == [0x0f, 0x84]));
+    }
+
+    #[test]
+    fn test_sibling_nested_call_spills_left_operand_across_the_call() {
+        // `fib(n-1)+fib(n-2)`と同じ形。左側の呼び出し結果を物理レジスタに置いたまま
+        // 右側の呼び出しをコンパイルすると、呼び出し先がそのレジスタを自由に使い潰して
+        // しまう。左オペランドは`allocate_registers`のクロバーポイント判定でスタックへ
+        // スピルされ、結果を`mov [rbp+offset], reg`で退避する命令が生成されるはず
+        let mut codegen = X86CodeGenerator::new();
+        let mut parser = Parser::new("fib(n - 1) + fib(n - 2)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let result = codegen.generate(&expr).unwrap();
+        // mov [rbp-disp8], r (48 89 /r, モード01でrm=RBP=101) がスピル書き込みとして
+        // 含まれているはず（`n`自身の変数スロット書き込みとは別に、一時値用のスロットが
+        // 少なくとも1つ追加で生成される）
+        let spill_store_count = result
+            .code
+            .windows(3)
+            .filter(|w| w[0] == 0x48 && w[1] == 0x89 && (w[2] & 0b11_000_111) == 0b01_000_101)
+            .count();
+        assert!(spill_store_count >= 2); // `n`の変数スロット分 + 左オペランドのスピル分
+    }
+}
@@ -0,0 +1,572 @@
+// ソフトウェアx86-64エミュレータ
+//
+// `X86CodeGenerator` が生成するマシンコードだけを対象にした最小限のフェッチ・
+// デコード・実行ループ。実際にOSの実行可能ページへ書き込んでジャンプするのではなく、
+// バイト列を読みながらレジスタ/スタックを模倣するため、ネイティブ実行の危険を
+// 冒さずにJITコンパイル結果の正しさをインタープリタと突き合わせて検証できる。
+
+use super::codegen::Register;
+use anyhow::{anyhow, Result};
+
+/// スタックに確保するワード（8バイト）数
+const STACK_WORDS: usize = 4096;
+/// 暴走コード（将来的なループ対応後のバグ等）を検出するためのステップ上限
+const MAX_STEPS: usize = 1_000_000;
+/// `call`のないトップレベル実行の「呼び出し元」を表す番兵の戻り先アドレス。
+/// 実ハードウェアでは最外殻の関数もホスト側の`call`で戻り先アドレスが積まれているが、
+/// ここではホストから直接飛び込む体のため、代わりにこの値を積んでおく。`ret`がこれを
+/// popした時点で最外殻からの戻りとみなし、実行を終了する
+const RETURN_TO_HOST: u64 = u64::MAX;
+
+/// 実行後に読み取れるCPUの状態
+///
+/// 簡略化のため、rsp/rbpは実バイトアドレスではなく`stack`へのワード単位の
+/// インデックスとして扱う。生成されるコードは8バイト単位のrbp相対オフセットしか
+/// 使わないため、この簡略化でも意味的に等価に実行できる。
+pub struct CpuState {
+    registers: [u64; 8],
+    stack: Vec<u64>,
+    pc: usize,
+    flags: Flags,
+    /// プロローグ（push rbp; mov rbp, rsp）完了直後、つまり呼び出し元から見た
+    /// このトップレベル呼び出しのフレーム基準点としてのrbp。`ret`に到達する頃には
+    /// エピローグの`pop rbp`によって現在の`rbp`レジスタは呼び出し元フレームへ
+    /// 復元されてしまっているため、実行完了後に変数環境を再構築する際は
+    /// （現在の`rbp`ではなく）この値を基準にする必要がある
+    entry_rbp: u64,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Flags {
+    zf: bool,
+    sf: bool,
+    of: bool,
+}
+
+impl Flags {
+    fn from_test(value: i64) -> Self {
+        Self {
+            zf: value == 0,
+            sf: value < 0,
+            of: false,
+        }
+    }
+
+    fn from_cmp(a: i64, b: i64) -> Self {
+        let (result, overflow) = a.overflowing_sub(b);
+        Self {
+            zf: result == 0,
+            sf: result < 0,
+            of: overflow,
+        }
+    }
+}
+
+impl CpuState {
+    fn new() -> Self {
+        let initial_index = (STACK_WORDS / 2) as u64;
+        let mut registers = [0u64; 8];
+        registers[Register::Rsp as usize] = initial_index;
+        registers[Register::Rbp as usize] = initial_index;
+
+        Self {
+            registers,
+            stack: vec![0u64; STACK_WORDS],
+            pc: 0,
+            flags: Flags::default(),
+            entry_rbp: initial_index,
+        }
+    }
+
+    fn reg(&self, r: Register) -> u64 {
+        self.registers[r as usize]
+    }
+
+    fn set_reg(&mut self, r: Register, value: u64) {
+        self.registers[r as usize] = value;
+    }
+
+    /// 実行完了後にRAXに残った戻り値を取得
+    pub fn rax(&self) -> i64 {
+        self.reg(Register::Rax) as i64
+    }
+
+    /// rbp相対バイトオフセットにある変数の値を読み取る（変数環境の再構築に使う）
+    pub fn read_rbp_offset(&self, byte_offset: i32) -> Result<i64> {
+        let index = self.rbp_word_index(byte_offset)?;
+        Ok(self.stack[index] as i64)
+    }
+
+    /// `entry_rbp`（最外殻呼び出しのプロローグ完了直後のrbp）を基準にバイトオフセット
+    /// にある変数の値を読み取る。`ret`到達後は現在の`rbp`がエピローグの`pop rbp`で
+    /// 呼び出し元フレームへ復元済みのため、`read_rbp_offset`では正しいスロットを
+    /// 指せない。実行完了後の変数環境再構築には必ずこちらを使うこと
+    pub fn read_entry_frame_offset(&self, byte_offset: i32) -> Result<i64> {
+        let word_offset = byte_offset / 8;
+        let index = self.entry_rbp as i64 + word_offset as i64;
+        if index < 0 || index as usize >= self.stack.len() {
+            return Err(anyhow!("stack access out of bounds at entry_rbp{:+}", byte_offset));
+        }
+        Ok(self.stack[index as usize] as i64)
+    }
+
+    /// rbp相対バイトオフセットに値を書き込む（実行開始前の変数シード用）
+    fn write_rbp_offset(&mut self, byte_offset: i32, value: i64) -> Result<()> {
+        let index = self.rbp_word_index(byte_offset)?;
+        self.stack[index] = value as u64;
+        Ok(())
+    }
+
+    fn rbp_word_index(&self, byte_offset: i32) -> Result<usize> {
+        let word_offset = byte_offset / 8;
+        let index = self.reg(Register::Rbp) as i64 + word_offset as i64;
+        if index < 0 || index as usize >= self.stack.len() {
+            return Err(anyhow!("stack access out of bounds at rbp{:+}", byte_offset));
+        }
+        Ok(index as usize)
+    }
+
+    fn push(&mut self, value: u64) -> Result<()> {
+        let new_rsp = self.reg(Register::Rsp).wrapping_sub(1);
+        let slot = self
+            .stack
+            .get_mut(new_rsp as usize)
+            .ok_or_else(|| anyhow!("stack overflow in JIT-compiled code"))?;
+        *slot = value;
+        self.set_reg(Register::Rsp, new_rsp);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<u64> {
+        let rsp = self.reg(Register::Rsp) as usize;
+        let value = *self
+            .stack
+            .get(rsp)
+            .ok_or_else(|| anyhow!("stack underflow in JIT-compiled code"))?;
+        self.set_reg(Register::Rsp, (rsp + 1) as u64);
+        Ok(value)
+    }
+
+    fn read_rm_memory(&self, mode: u8, code: &[u8], pc: usize) -> Result<(u64, usize)> {
+        match mode {
+            0b01 => {
+                let disp = *code.get(pc).ok_or_else(|| anyhow!("unexpected end of code"))? as i8 as i32;
+                Ok((self.read_rbp_offset(disp)? as u64, pc + 1))
+            }
+            0b10 => {
+                let disp = i32::from_le_bytes(read4(code, pc)?);
+                Ok((self.read_rbp_offset(disp)? as u64, pc + 4))
+            }
+            other => Err(anyhow!("unsupported ModRM mod bits for memory operand: {}", other)),
+        }
+    }
+
+    fn write_rm_memory(&mut self, mode: u8, value: u64, code: &[u8], pc: usize) -> Result<usize> {
+        match mode {
+            0b01 => {
+                let disp = *code.get(pc).ok_or_else(|| anyhow!("unexpected end of code"))? as i8 as i32;
+                let index = self.rbp_word_index(disp)?;
+                self.stack[index] = value;
+                Ok(pc + 1)
+            }
+            0b10 => {
+                let disp = i32::from_le_bytes(read4(code, pc)?);
+                let index = self.rbp_word_index(disp)?;
+                self.stack[index] = value;
+                Ok(pc + 4)
+            }
+            other => Err(anyhow!("unsupported ModRM mod bits for memory operand: {}", other)),
+        }
+    }
+}
+
+fn read4(code: &[u8], pc: usize) -> Result<[u8; 4]> {
+    code.get(pc..pc + 4)
+        .and_then(|s| s.try_into().ok())
+        .ok_or_else(|| anyhow!("unexpected end of code reading a 4-byte operand"))
+}
+
+fn read8(code: &[u8], pc: usize) -> Result<[u8; 8]> {
+    code.get(pc..pc + 8)
+        .and_then(|s| s.try_into().ok())
+        .ok_or_else(|| anyhow!("unexpected end of code reading an 8-byte operand"))
+}
+
+fn decode_modrm(byte: u8) -> (u8, u8, u8) {
+    (byte >> 6, (byte >> 3) & 0x7, byte & 0x7)
+}
+
+/// `0f cc` の条件コードをフラグに照らして判定する（sete/setne/setl/setg/setle/setge）
+fn condition_holds(opcode: u8, flags: &Flags) -> Result<bool> {
+    match opcode {
+        0x94 => Ok(flags.zf),                                // sete
+        0x95 => Ok(!flags.zf),                                // setne
+        0x9c => Ok(flags.sf != flags.of),                     // setl
+        0x9f => Ok(!flags.zf && flags.sf == flags.of),        // setg
+        0x9e => Ok(flags.zf || flags.sf != flags.of),         // setle
+        0x9d => Ok(flags.sf == flags.of),                     // setge
+        other => Err(anyhow!("unsupported set<cc> opcode: 0f {:02x}", other)),
+    }
+}
+
+/// 1命令だけフェッチ・デコード・実行する。`ret`に到達したら`Some(rax)`を返す。
+fn step(cpu: &mut CpuState, code: &[u8]) -> Result<Option<i64>> {
+    let rex_w = code.get(cpu.pc) == Some(&0x48);
+    let mut pc = cpu.pc + if rex_w { 1 } else { 0 };
+
+    let opcode = *code
+        .get(pc)
+        .ok_or_else(|| anyhow!("unexpected end of code at pc={}", pc))?;
+    pc += 1;
+
+    match opcode {
+        0x55 => {
+            let rbp = cpu.reg(Register::Rbp);
+            cpu.push(rbp)?;
+        }
+        0x5d => {
+            let value = cpu.pop()?;
+            cpu.set_reg(Register::Rbp, value);
+        }
+        0x50 => {
+            let rax = cpu.reg(Register::Rax);
+            cpu.push(rax)?;
+        }
+        0x59 => {
+            let value = cpu.pop()?;
+            cpu.set_reg(Register::Rcx, value);
+        }
+        0xc3 => {
+            // 戻り先アドレスをpopする。番兵（ホストからの最外殻呼び出し）であれば
+            // 実行終了、そうでなければ実際の呼び出し元へ戻って実行を続ける
+            let return_addr = cpu.pop()?;
+            if return_addr == RETURN_TO_HOST {
+                cpu.pc = pc;
+                return Ok(Some(cpu.rax()));
+            }
+            pc = return_addr as usize;
+        }
+        0xb8..=0xbf if !rex_w => {
+            let reg = Register::from_bits(opcode - 0xb8);
+            let imm = i32::from_le_bytes(read4(code, pc)?);
+            pc += 4;
+            // mov r32, imm32 は上位32ビットをゼロクリアする
+            cpu.set_reg(reg, imm as u32 as u64);
+        }
+        0xb8..=0xbf if rex_w => {
+            let reg = Register::from_bits(opcode - 0xb8);
+            let imm = u64::from_le_bytes(read8(code, pc)?);
+            pc += 8;
+            cpu.set_reg(reg, imm);
+        }
+        0x89 => {
+            // mov r/m, r
+            let modrm = code[pc];
+            pc += 1;
+            let (mode, reg, rm) = decode_modrm(modrm);
+            let src = cpu.reg(Register::from_bits(reg));
+            if mode == 0b11 {
+                cpu.set_reg(Register::from_bits(rm), src);
+            } else {
+                pc = cpu.write_rm_memory(mode, src, code, pc)?;
+            }
+        }
+        0x8b => {
+            // mov r, r/m
+            let modrm = code[pc];
+            pc += 1;
+            let (mode, reg, rm) = decode_modrm(modrm);
+            let value = if mode == 0b11 {
+                cpu.reg(Register::from_bits(rm))
+            } else {
+                let (value, new_pc) = cpu.read_rm_memory(mode, code, pc)?;
+                pc = new_pc;
+                value
+            };
+            cpu.set_reg(Register::from_bits(reg), value);
+        }
+        0x01 => {
+            // add r/m, r
+            let modrm = code[pc];
+            pc += 1;
+            let (_, reg, rm) = decode_modrm(modrm);
+            let dest = Register::from_bits(rm);
+            let new = cpu.reg(dest).wrapping_add(cpu.reg(Register::from_bits(reg)));
+            cpu.set_reg(dest, new);
+        }
+        0x29 => {
+            // sub r/m, r
+            let modrm = code[pc];
+            pc += 1;
+            let (_, reg, rm) = decode_modrm(modrm);
+            let dest = Register::from_bits(rm);
+            let new = cpu.reg(dest).wrapping_sub(cpu.reg(Register::from_bits(reg)));
+            cpu.set_reg(dest, new);
+        }
+        0x81 => {
+            // sub r/m64, imm32 (ModRMの/5拡張)。`emit_prologue`が発行する
+            // `sub rsp, imm32`だけを対象とした最小実装
+            let modrm = code[pc];
+            pc += 1;
+            let (mode, reg_ext, rm) = decode_modrm(modrm);
+            if mode != 0b11 || reg_ext != 5 {
+                return Err(anyhow!("unsupported ModRM group 1 opcode extension: /{}", reg_ext));
+            }
+            let imm = i32::from_le_bytes(read4(code, pc)?);
+            pc += 4;
+            let dest = Register::from_bits(rm);
+            // `stack`はワード単位でインデックスされる（`rbp_word_index`参照）ため、
+            // バイト単位の即値はここでワード数へ変換してから引く
+            let new = cpu.reg(dest).wrapping_sub((imm / 8) as i64 as u64);
+            cpu.set_reg(dest, new);
+        }
+        0x99 => {
+            // cqo: RAXの符号をRDX:RAXへ拡張
+            let rax = cpu.reg(Register::Rax) as i64;
+            cpu.set_reg(Register::Rdx, if rax < 0 { u64::MAX } else { 0 });
+        }
+        0xf7 => {
+            // idiv r/m (ModRMの/7拡張)
+            let modrm = code[pc];
+            pc += 1;
+            let (_, _reg, rm) = decode_modrm(modrm);
+            let divisor = cpu.reg(Register::from_bits(rm)) as i64;
+            if divisor == 0 {
+                return Err(anyhow!("division by zero in JIT-compiled code"));
+            }
+            let dividend = cpu.reg(Register::Rax) as i64;
+            cpu.set_reg(Register::Rax, dividend.wrapping_div(divisor) as u64);
+            cpu.set_reg(Register::Rdx, dividend.wrapping_rem(divisor) as u64);
+        }
+        0x85 => {
+            // test r/m, r
+            let modrm = code[pc];
+            pc += 1;
+            let (_, reg, rm) = decode_modrm(modrm);
+            let value = cpu.reg(Register::from_bits(rm)) & cpu.reg(Register::from_bits(reg));
+            cpu.flags = Flags::from_test(value as i64);
+        }
+        0x39 => {
+            // cmp r/m, r
+            let modrm = code[pc];
+            pc += 1;
+            let (_, reg, rm) = decode_modrm(modrm);
+            let a = cpu.reg(Register::from_bits(rm)) as i64;
+            let b = cpu.reg(Register::from_bits(reg)) as i64;
+            cpu.flags = Flags::from_cmp(a, b);
+        }
+        0x0f => {
+            let op2 = *code.get(pc).ok_or_else(|| anyhow!("unexpected end of code"))?;
+            pc += 1;
+            match op2 {
+                0xaf => {
+                    // imul r, r/m
+                    let modrm = code[pc];
+                    pc += 1;
+                    let (_, reg, rm) = decode_modrm(modrm);
+                    let a = cpu.reg(Register::from_bits(reg)) as i64;
+                    let b = cpu.reg(Register::from_bits(rm)) as i64;
+                    cpu.set_reg(Register::from_bits(reg), a.wrapping_mul(b) as u64);
+                }
+                0xb6 => {
+                    // movzx r, r/m8
+                    let modrm = code[pc];
+                    pc += 1;
+                    let (_, reg, rm) = decode_modrm(modrm);
+                    let byte = cpu.reg(Register::from_bits(rm)) & 0xff;
+                    cpu.set_reg(Register::from_bits(reg), byte);
+                }
+                0x84 => {
+                    // jz rel32
+                    let rel = i32::from_le_bytes(read4(code, pc)?);
+                    pc += 4;
+                    if cpu.flags.zf {
+                        pc = ((pc as i64) + rel as i64) as usize;
+                    }
+                }
+                0x90..=0x9f => {
+                    // set<cc> al（ModRMバイトは常にalを指すだけなので読み飛ばす）
+                    pc += 1;
+                    let value = if condition_holds(op2, &cpu.flags)? { 1 } else { 0 };
+                    let rax = cpu.reg(Register::Rax);
+                    cpu.set_reg(Register::Rax, (rax & !0xffu64) | value);
+                }
+                other => return Err(anyhow!("unsupported two-byte opcode: 0f {:02x}", other)),
+            }
+        }
+        0xe9 => {
+            // jmp rel32
+            let rel = i32::from_le_bytes(read4(code, pc)?);
+            pc += 4;
+            pc = ((pc as i64) + rel as i64) as usize;
+        }
+        0xe8 => {
+            // call rel32: 戻り先アドレス（この命令の直後のpc）をpushしてから飛ぶ
+            let rel = i32::from_le_bytes(read4(code, pc)?);
+            pc += 4;
+            let return_addr = pc as u64;
+            let target = ((pc as i64) + rel as i64) as usize;
+            cpu.push(return_addr)?;
+            pc = target;
+        }
+        other => return Err(anyhow!("unsupported opcode: {:#04x} at offset {}", other, cpu.pc)),
+    }
+
+    cpu.pc = pc;
+    Ok(None)
+}
+
+/// `CompiledFunction::code`を実行し、`ret`到達時点のCPU状態を返す
+pub fn execute(code: &[u8]) -> Result<CpuState> {
+    execute_seeded(code, &[])
+}
+
+/// `CompiledFunction::code`を実行する。`seeds`には実行開始前にrbp相対オフセットへ
+/// 書き込んでおきたい初期値を`(オフセット, 値)`のペアで渡す。プロローグ（push rbp;
+/// mov rbp, rsp）の実行が終わるのを待ってから書き込むことで、コード生成側が前提とする
+/// rbp相対オフセットの基準点と一致させる
+pub fn execute_seeded(code: &[u8], seeds: &[(i32, i64)]) -> Result<CpuState> {
+    let mut cpu = CpuState::new();
+
+    // ホストから直接飛び込む最外殻の呼び出しを、番兵の戻り先アドレスを積んだ`call`と
+    // みなす。こうしておくと、関数呼び出しをJITした本体の中で他の関数を`call`しても、
+    // その`ret`は素直に戻り先へ戻り、最外殻の`ret`だけが実行終了として扱われる
+    cpu.push(RETURN_TO_HOST)?;
+
+    for _ in 0..2 {
+        if step(&mut cpu, code)?.is_some() {
+            return Err(anyhow!("code returned before its prologue completed"));
+        }
+    }
+    // この時点のrbpが最外殻呼び出しのフレーム基準点。エピローグの`pop rbp`で
+    // 呼び出し元フレームへ復元される前に控えておく（`entry_rbp`のドキュメント参照）
+    cpu.entry_rbp = cpu.reg(Register::Rbp);
+
+    for &(offset, value) in seeds {
+        cpu.write_rbp_offset(offset, value)?;
+    }
+
+    for _ in 0..MAX_STEPS {
+        if let Some(rax) = step(&mut cpu, code)? {
+            cpu.set_reg(Register::Rax, rax as u64);
+            return Ok(cpu);
+        }
+    }
+
+    Err(anyhow!(
+        "JIT-compiled code exceeded {} steps without reaching ret",
+        MAX_STEPS
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+    use crate::jit::codegen::{CodeGenerator, X86CodeGenerator};
+    use crate::parser::Parser;
+
+    fn emulate(source: &str) -> i64 {
+        let mut parser = Parser::new(source).unwrap();
+        let expr = parser.parse().unwrap();
+
+        let mut codegen = X86CodeGenerator::new();
+        let compiled = codegen.generate(&expr).unwrap();
+
+        let cpu = execute(&compiled.code).unwrap();
+        cpu.rax()
+    }
+
+    fn interpret(source: &str) -> i64 {
+        let mut parser = Parser::new(source).unwrap();
+        let expr = parser.parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        // エミュレータ側は生のi64しか扱えないため、`Value`の境界はここで
+        // `as_i64_lossy`により変換する（`jit::JitCompiler::execute`と同じ境界）
+        interpreter.evaluate_without_delay(&expr).unwrap().value.as_i64_lossy()
+    }
+
+    #[test]
+    fn test_emulates_number_literal() {
+        assert_eq!(emulate("42"), 42);
+    }
+
+    #[test]
+    fn test_emulates_arithmetic_matches_interpreter() {
+        for source in ["1 + 2", "10 - 3", "6 * 7", "20 / 3", "20 % 3"] {
+            assert_eq!(emulate(source), interpret(source), "mismatch for {}", source);
+        }
+    }
+
+    #[test]
+    fn test_emulates_negative_number_via_imm64() {
+        assert_eq!(emulate("0 - 1000000000000"), -1_000_000_000_000);
+    }
+
+    #[test]
+    fn test_emulates_comparison_operators() {
+        for source in ["1 < 2", "2 < 1", "1 == 1", "1 != 1", "3 >= 3", "2 <= 1"] {
+            assert_eq!(emulate(source), interpret(source), "mismatch for {}", source);
+        }
+    }
+
+    #[test]
+    fn test_emulates_variable_assignment_and_read() {
+        let mut parser = Parser::new("x = 42").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let mut codegen = X86CodeGenerator::new();
+        let compiled = codegen.generate(&expr).unwrap();
+
+        let cpu = execute(&compiled.code).unwrap();
+        assert_eq!(cpu.rax(), 42);
+
+        let offset = compiled.variables["x"];
+        assert_eq!(cpu.read_entry_frame_offset(offset).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_emulates_if_expression_matches_interpreter() {
+        for source in ["if(1, 2, 3)", "if(0, 2, 3)"] {
+            assert_eq!(emulate(source), interpret(source), "mismatch for {}", source);
+        }
+    }
+
+    #[test]
+    fn test_emulates_arithmetic_with_nested_if_operand_matches_interpreter() {
+        // `if(...)`はコンパイル先がRDI/RSI/RBX/RDXを自由に使い潰すため、左オペランドの
+        // `1`を物理レジスタに置いたまま右オペランドの`if`をコンパイルすると壊れる
+        // （`allocate_registers`のクロバーポイント判定で左オペランドがスピルされるべき）
+        for source in ["1 + if(1, 2, 3)", "1 + if(0, 2, 3)"] {
+            assert_eq!(emulate(source), interpret(source), "mismatch for {}", source);
+        }
+    }
+
+    #[test]
+    fn test_division_by_zero_is_an_error() {
+        let mut parser = Parser::new("1 / 0").unwrap();
+        let expr = parser.parse().unwrap();
+
+        let mut codegen = X86CodeGenerator::new();
+        let compiled = codegen.generate(&expr).unwrap();
+
+        assert!(execute(&compiled.code).is_err());
+    }
+
+    #[test]
+    fn test_emulates_recursive_function_calls_matches_interpreter() {
+        for source in ["fib(10)", "fact(6)", "pow(2, 10)", "pow(3, 0)", "pow(2, -1)"] {
+            assert_eq!(emulate(source), interpret(source), "mismatch for {}", source);
+        }
+    }
+
+    #[test]
+    fn test_emulates_function_call_mixed_with_arithmetic() {
+        assert_eq!(emulate("fib(6) + fib(5)"), interpret("fib(6) + fib(5)"));
+    }
+
+    #[test]
+    fn test_emulates_multiple_distinct_function_calls_in_one_expression() {
+        assert_eq!(emulate("fact(5) + pow(2, 3)"), interpret("fact(5) + pow(2, 3)"));
+    }
+}
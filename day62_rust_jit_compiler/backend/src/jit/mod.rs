@@ -1,40 +1,54 @@
 // JITコンパイラモジュール
 
 pub mod codegen;
+mod emulator;
 
-use crate::ast::{Expr, ExecutionResult, JitStats};
+use crate::ast::{hash_expr, Environment, Expr, ExecutionResult, JitStats, Value};
 use crate::interpreter::Interpreter;
 use crate::parser::Parser;
 use anyhow::Result;
-use codegen::{CompiledFunction, X86CodeGenerator};
+use codegen::{Arm64CodeGenerator, CodeGenerator, CompiledFunction, X86CodeGenerator};
 use std::collections::HashMap;
 use std::time::Instant;
 
-/// 実行可能メモリ管理（シミュレーション版）
+/// 実行可能メモリ管理（ソフトウェアエミュレーション版）
 ///
-/// 注意: 実際のJIT実行は安全のため無効化されています。
-/// マシンコード生成は正常に動作しますが、実行はインタープリタで行われます。
+/// OSの実行可能ページへ書き込んで実際にジャンプするのではなく、`emulator`モジュールの
+/// 簡易x86-64エミュレータ上でマシンコードを解釈実行する。ネイティブ実行の危険を冒さずに、
+/// コード生成結果がインタープリタと一致するかを検証できる「正しさのオラクル」として使える。
 pub struct ExecutableMemory {
     code: Vec<u8>,
-    simulated: bool,
+    variables: HashMap<String, i32>,
 }
 
 impl ExecutableMemory {
-    /// 実行可能メモリページを作成（シミュレーション）
-    pub fn new(code: &[u8]) -> Result<Self> {
-        println!("⚠️  実行可能メモリはシミュレーションモードです");
-
+    /// 実行可能メモリページを作成（エミュレーション）
+    pub fn new(code: &[u8], variables: HashMap<String, i32>) -> Result<Self> {
         Ok(Self {
             code: code.to_vec(),
-            simulated: true,
+            variables,
         })
     }
 
-    /// 関数として実行（シミュレーション）
-    pub unsafe fn execute(&self) -> i64 {
-        // 実際のマシンコード実行は危険なため、ダミー値を返す
-        println!("⚡ JIT実行シミュレーション ({}バイトのマシンコード)", self.code.len());
-        42 // ダミー値
+    /// 関数として実行し、戻り値（RAX）と実行後の変数環境を返す。
+    /// `initial_values`には、この関数が参照する変数のうち直前までの実行環境に
+    /// 既に値が存在するものを渡す。関数内で一度も代入されず読み取られるだけの変数
+    /// （＝ループ条件など、外部で設定された変数）はここでシードされた値から始まる
+    pub fn execute(&self, initial_values: &HashMap<String, i64>) -> Result<(i64, HashMap<String, i64>)> {
+        let seeds: Vec<(i32, i64)> = self
+            .variables
+            .iter()
+            .filter_map(|(name, &offset)| initial_values.get(name).map(|&value| (offset, value)))
+            .collect();
+
+        let cpu = emulator::execute_seeded(&self.code, &seeds)?;
+
+        let mut environment = HashMap::new();
+        for (name, &offset) in &self.variables {
+            environment.insert(name.clone(), cpu.read_entry_frame_offset(offset)?);
+        }
+
+        Ok((cpu.rax(), environment))
     }
 
     /// 生成されたマシンコードを取得
@@ -56,7 +70,7 @@ pub struct JitCompiler {
     stats: JitStats,
     hot_threshold: u64,
     interpreter: Interpreter,
-    codegen: X86CodeGenerator,
+    codegen: Box<dyn CodeGenerator>,
     jit_cache: HashMap<u64, JitEntry>,
     max_cache_size: usize, // キャッシュサイズ制限を追加
 }
@@ -67,16 +81,42 @@ impl JitCompiler {
             stats: JitStats::default(),
             hot_threshold: 5, // 5回実行でJITコンパイル（より早く体感）
             interpreter: Interpreter::new(),
-            codegen: X86CodeGenerator::new(),
+            codegen: Self::select_codegen(std::env::consts::ARCH),
             jit_cache: HashMap::new(),
             max_cache_size: 100, // 最大100エントリまで
         }
     }
 
+    /// 実行ホストのターゲットアーキテクチャ名から、デフォルトで使うコード生成バックエンドを
+    /// 選ぶ。`std::env::consts::ARCH`という文字列を受け取る形にしておくことで、実際の
+    /// ビルドターゲットに依存せず選択ロジック自体を単体テストできる
+    fn select_codegen(arch: &str) -> Box<dyn CodeGenerator> {
+        if arch == "aarch64" {
+            Box::new(Arm64CodeGenerator::new())
+        } else {
+            Box::new(X86CodeGenerator::new())
+        }
+    }
+
+    /// 任意のコード生成バックエンドを指定してJITコンパイラを構築する。
+    /// 例えば`Arm64CodeGenerator`を渡せば、同じASTパイプラインのままAArch64向けの
+    /// マシンコードを生成するJITコンパイラになる
+    pub fn with_codegen(codegen: Box<dyn CodeGenerator>) -> Self {
+        Self {
+            codegen,
+            ..Self::new()
+        }
+    }
+
     /// 式がJITコンパイル可能かチェック
     fn is_jit_compilable(&self, expr: &Expr) -> bool {
         match expr {
             Expr::Number(_) => true,
+            // 真偽値は比較演算の結果と同じく0/1の整数として表現できるためJIT対象にできる
+            Expr::Bool(_) => true,
+            // Phase 3のコード生成はまだ浮動小数点・文字列に対応していない
+            Expr::Float(_) => false,
+            Expr::Str(_) => false,
             Expr::Variable(_) => true,
             Expr::Binary { left, right, .. } => {
                 self.is_jit_compilable(left) && self.is_jit_compilable(right)
@@ -87,8 +127,154 @@ impl JitCompiler {
                     && self.is_jit_compilable(true_expr)
                     && self.is_jit_compilable(false_expr)
             }
-            // 関数呼び出しはJITコンパイル対象外（デモ目的）
-            Expr::FunctionCall { .. } => false,
+            Expr::While { condition, body } => {
+                self.is_jit_compilable(condition) && self.is_jit_compilable(body)
+            }
+            Expr::Sequence(statements) => statements.iter().all(|s| self.is_jit_compilable(s)),
+            // `fib`/`fact`/`pow`はコード生成側（`X86CodeGenerator::compile_builtin_function`）
+            // が本体をこのコンパイラ自身のExpr ASTとして埋め込めるため、JIT対象にできる。
+            // それ以外の名前はインタープリタでも「Unknown function」になるだけなので、
+            // 呼び出し可能性の判定までは立ち入らずJIT対象として引数だけチェックする
+            Expr::FunctionCall { args, .. } => args.iter().all(|a| self.is_jit_compilable(a)),
+            // 論理演算子・単項演算子はPhase 3のコード生成が未対応
+            Expr::Logical { .. } => false,
+            Expr::Unary { .. } => false,
+            Expr::Fallback { primary, fallback } => {
+                self.is_jit_compilable(primary) && self.is_jit_compilable(fallback)
+            }
+        }
+    }
+
+    /// 式の部分木に含まれる`Expr::While`ループをすべて集める（ループ単位のホットスポット検出用）
+    fn find_loops<'a>(expr: &'a Expr, out: &mut Vec<&'a Expr>) {
+        match expr {
+            Expr::While { condition, body } => {
+                Self::find_loops(condition, out);
+                Self::find_loops(body, out);
+                out.push(expr);
+            }
+            Expr::Binary { left, right, .. } => {
+                Self::find_loops(left, out);
+                Self::find_loops(right, out);
+            }
+            Expr::Assignment { value, .. } => Self::find_loops(value, out),
+            Expr::FunctionCall { args, .. } => {
+                for arg in args {
+                    Self::find_loops(arg, out);
+                }
+            }
+            Expr::If { condition, true_expr, false_expr } => {
+                Self::find_loops(condition, out);
+                Self::find_loops(true_expr, out);
+                Self::find_loops(false_expr, out);
+            }
+            Expr::Logical { left, right, .. } => {
+                Self::find_loops(left, out);
+                Self::find_loops(right, out);
+            }
+            Expr::Unary { operand, .. } => Self::find_loops(operand, out),
+            Expr::Sequence(statements) => {
+                for statement in statements {
+                    Self::find_loops(statement, out);
+                }
+            }
+            Expr::Fallback { primary, fallback } => {
+                Self::find_loops(primary, out);
+                Self::find_loops(fallback, out);
+            }
+            Expr::Number(_) | Expr::Bool(_) | Expr::Float(_) | Expr::Str(_) | Expr::Variable(_) => {}
+        }
+    }
+
+    /// `Expr::Fallback`をこの時点の`Environment`を見て具体的な枝へ解決してからコード生成へ渡す。
+    /// JITの実行モデルは生のレジスタ/スタックしか扱えず「未束縛」と「値が0」を実行時に
+    /// 区別できないため、分岐をマシンコードとして埋め込むことができない。そこでJIT
+    /// コンパイルを行うまさにその瞬間に見えている環境だけを見て、どちらの枝を埋め込むか
+    /// その場で確定させる（以後そのキャッシュ済みコードが別の束縛状況で再利用されても
+    /// 埋め込んだ枝は変わらないという既知の制約が生じるが、ホットパスは通常同じ変数が
+    /// 同じ束縛状況で繰り返し呼ばれるため実用上は問題にならない）
+    fn resolve_fallbacks(expr: &Expr, env: &Environment) -> Expr {
+        match expr {
+            Expr::Fallback { primary, fallback } => match primary.as_ref() {
+                Expr::Variable(name) if env.get(name).is_none() => Self::resolve_fallbacks(fallback, env),
+                _ => Self::resolve_fallbacks(primary, env),
+            },
+            Expr::Binary { left, op, right } => Expr::Binary {
+                left: Box::new(Self::resolve_fallbacks(left, env)),
+                op: op.clone(),
+                right: Box::new(Self::resolve_fallbacks(right, env)),
+            },
+            Expr::Assignment { name, value } => Expr::Assignment {
+                name: name.clone(),
+                value: Box::new(Self::resolve_fallbacks(value, env)),
+            },
+            Expr::FunctionCall { name, args } => Expr::FunctionCall {
+                name: name.clone(),
+                args: args.iter().map(|a| Self::resolve_fallbacks(a, env)).collect(),
+            },
+            Expr::If { condition, true_expr, false_expr } => Expr::If {
+                condition: Box::new(Self::resolve_fallbacks(condition, env)),
+                true_expr: Box::new(Self::resolve_fallbacks(true_expr, env)),
+                false_expr: Box::new(Self::resolve_fallbacks(false_expr, env)),
+            },
+            Expr::Logical { left, op, right } => Expr::Logical {
+                left: Box::new(Self::resolve_fallbacks(left, env)),
+                op: op.clone(),
+                right: Box::new(Self::resolve_fallbacks(right, env)),
+            },
+            Expr::Unary { op, operand } => Expr::Unary {
+                op: op.clone(),
+                operand: Box::new(Self::resolve_fallbacks(operand, env)),
+            },
+            Expr::While { condition, body } => Expr::While {
+                condition: Box::new(Self::resolve_fallbacks(condition, env)),
+                body: Box::new(Self::resolve_fallbacks(body, env)),
+            },
+            Expr::Sequence(statements) => {
+                Expr::Sequence(statements.iter().map(|s| Self::resolve_fallbacks(s, env)).collect())
+            }
+            Expr::Number(_) | Expr::Float(_) | Expr::Bool(_) | Expr::Str(_) | Expr::Variable(_) => expr.clone(),
+        }
+    }
+
+    /// インタープリタが累積しているループごとのバックエッジ回数を見て、
+    /// まだコンパイルされていないホットループをその場でJITコンパイルする。
+    /// これにより、ループ自身が`hot_threshold`回を超えて回った時点で次回以降の実行から
+    /// JIT化される（包む側のトップレベル式が何度実行されたかとは無関係に判定できる）
+    fn compile_hot_loops(&mut self, expr: &Expr) {
+        let mut loops = Vec::new();
+        Self::find_loops(expr, &mut loops);
+
+        for loop_expr in loops {
+            let loop_hash = hash_expr(loop_expr);
+
+            if self.jit_cache.get(&loop_hash).map(|e| e.compiled_function.is_some()).unwrap_or(false) {
+                continue; // 既にコンパイル済み
+            }
+            if !self.is_jit_compilable(loop_expr) {
+                continue;
+            }
+
+            let iterations = self.interpreter.loop_iteration_count(loop_hash);
+            if iterations < self.hot_threshold {
+                continue;
+            }
+
+            let resolved_loop_expr = Self::resolve_fallbacks(loop_expr, self.interpreter.get_environment());
+            match self.codegen.generate(&resolved_loop_expr) {
+                Ok(compiled_func) => {
+                    println!("🔥🔁 ホットループ検出: バックエッジ{}回でループ本体をJITコンパイル ({:#x})",
+                             iterations, loop_hash);
+                    self.stats.jit_compilations += 1;
+                    let entry = self.jit_cache.entry(loop_hash).or_insert_with(|| JitEntry {
+                        expr_hash: loop_hash,
+                        execution_count: iterations,
+                        compiled_function: None,
+                    });
+                    entry.compiled_function = Some(compiled_func);
+                }
+                Err(e) => println!("❌ ループJITコンパイル失敗: {}", e),
+            }
         }
     }
 
@@ -135,12 +321,13 @@ impl JitCompiler {
                      self.jit_cache[&expr_hash].execution_count);
 
             let start = Instant::now();
-            match self.codegen.generate(expr) {
+            let resolved_expr = Self::resolve_fallbacks(expr, self.interpreter.get_environment());
+            match self.codegen.generate(&resolved_expr) {
                 Ok(compiled_func) => {
                     let compilation_time = start.elapsed().as_nanos() as u64;
 
-                    println!("✅ JITコンパイル完了: {}バイトのマシンコード生成 ({}ns)",
-                             compiled_func.code.len(), compilation_time);
+                    println!("✅ JITコンパイル完了[{}]: {}バイトのマシンコード生成 ({}ns)",
+                             self.codegen.target_name(), compiled_func.code.len(), compilation_time);
 
                     // マシンコードの一部を16進数で表示
                     let code_preview: String = compiled_func.code
@@ -168,21 +355,44 @@ impl JitCompiler {
 
         // 実行
         let mut result = if let Some(entry) = self.jit_cache.get(&expr_hash) {
+            // `emulator`モジュールはx86-64命令しか解釈できないため、実際に実行できるのは
+            // x86-64バックエンドが生成したマシンコードのみ。他のバックエンド（AArch64など）は
+            // マシンコード生成までは行えるが、対応するエミュレータがまだ存在しないため
+            // インタープリタ実行にフォールバックする
             if let Some(ref compiled_func) = entry.compiled_function {
-                // JIT実行シミュレーション（高速化されている想定）
-                println!("⚡ JIT実行シミュレーション ({}バイトのマシンコード使用予定)",
-                         compiled_func.code.len());
-                // JIT実行時は遅延なしで高速実行
-                let start_eval = Instant::now();
-                let eval_result = self.interpreter.evaluate_without_delay(expr)?;
-                let eval_time = start_eval.elapsed().as_nanos() as u64;
-
-                ExecutionResult {
-                    value: eval_result.value,
-                    environment: eval_result.environment,
-                    execution_time_ns: eval_time,
-                    compilation_time_ns: None,
-                    was_jit_compiled: true,
+                if self.codegen.target_name() == "x86-64" {
+                    // 生成されたマシンコードをエミュレータ上で実際に実行する
+                    println!("⚡ JIT実行: {}バイトのマシンコードをエミュレータ上で実行",
+                             compiled_func.code.len());
+                    let start_eval = Instant::now();
+                    let memory = ExecutableMemory::new(&compiled_func.code, compiled_func.variables.clone())?;
+                    // JITのコード生成・実行エミュレータは生の整数レジスタしか扱えないため、
+                    // `Value`の環境はこの境界でのみ`as_i64_lossy`によって生の`i64`へ変換する
+                    // （Floatのシード値は切り捨てられる。Phase 3ではFloatはそもそもJIT対象外）
+                    let raw_env: HashMap<String, i64> = self.interpreter
+                        .get_environment()
+                        .variables
+                        .iter()
+                        .map(|(name, value)| (name.clone(), value.as_i64_lossy()))
+                        .collect();
+                    let (value, environment) = memory.execute(&raw_env)?;
+                    let eval_time = start_eval.elapsed().as_nanos() as u64;
+
+                    // JIT実行で更新された変数をインタープリタの環境にも反映し、
+                    // この後インタープリタ実行に戻っても状態が食い違わないようにする
+                    for (name, &value) in &environment {
+                        self.interpreter.set_variable(name.clone(), Value::Int(value));
+                    }
+
+                    ExecutionResult {
+                        value: Value::Int(value),
+                        environment: environment.into_iter().map(|(name, value)| (name, Value::Int(value))).collect(),
+                        execution_time_ns: eval_time,
+                        compilation_time_ns: None,
+                        was_jit_compiled: true,
+                    }
+                } else {
+                    self.interpreter.evaluate(expr)?
                 }
             } else {
                 // インタープリタ実行（遅延あり）
@@ -192,6 +402,11 @@ impl JitCompiler {
             self.interpreter.evaluate(expr)?
         };
 
+        // インタープリタ実行だった場合、ループ単位のバックエッジ回数を見てホットループを検出する
+        if !result.was_jit_compiled {
+            self.compile_hot_loops(expr);
+        }
+
         // 実行時間を外側のタイマーで測定せず、内側の実行時間を使用
         let was_jit_compiled = result.was_jit_compiled;
 
@@ -211,14 +426,7 @@ impl JitCompiler {
 
     /// 式のハッシュ値を計算（簡易版）
     fn hash_expr(&self, expr: &Expr) -> u64 {
-        // 簡易的な実装：式を文字列化してハッシュ
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let expr_str = format!("{:?}", expr);
-        let mut hasher = DefaultHasher::new();
-        expr_str.hash(&mut hasher);
-        hasher.finish()
+        hash_expr(expr)
     }
 
     /// 文字列からパースして実行
@@ -369,6 +577,68 @@ mod tests {
         assert_eq!(expr1_count, Some(2));
     }
 
+    #[test]
+    fn test_hot_loop_is_compiled_after_first_invocation() {
+        let mut jit = JitCompiler::new();
+        jit.execute_string("i = 0").unwrap();
+        jit.execute_string("sum = 0").unwrap();
+
+        // ループ自体のバックエッジ回数（10回）がhot_threshold（5回）を超えるため、
+        // トップレベルの式としては1回しか実行していなくてもこの呼び出しの直後に
+        // ループ本体がJITコンパイルされる
+        let result = jit.execute_string("while(i < 10, i = i + 1; sum = sum + i)").unwrap();
+        assert_eq!(result.value, 55); // 1+2+..+10
+        assert!(jit.stats.jit_compilations > 0);
+
+        // 次に全く同じループ式を実行すると、既にコンパイル済みのためJIT実行されるはず
+        jit.execute_string("i = 0").unwrap();
+        jit.execute_string("sum = 0").unwrap();
+        let result2 = jit.execute_string("while(i < 10, i = i + 1; sum = sum + i)").unwrap();
+        assert_eq!(result2.value, 55);
+        assert!(result2.was_jit_compiled);
+    }
+
+    #[test]
+    fn test_recursive_function_call_is_jit_compiled_and_matches_interpreter() {
+        let mut jit = JitCompiler::new();
+
+        let mut last = None;
+        for _ in 0..12 {
+            last = Some(jit.execute_string("fib(10)").unwrap());
+        }
+        let result = last.unwrap();
+        assert_eq!(result.value, 55);
+        assert!(jit.stats.jit_compilations > 0);
+        assert!(result.was_jit_compiled);
+    }
+
+    #[test]
+    fn test_with_codegen_selects_arm64_backend() {
+        use codegen::Arm64CodeGenerator;
+
+        let mut jit = JitCompiler::with_codegen(Box::new(Arm64CodeGenerator::new()));
+
+        // ホットスポット検出自体はバックエンドに依存しないため、x86版と同じ回数回せばよい
+        for _ in 0..12 {
+            let result = jit.execute_string("1 + 2 * 3").unwrap();
+            assert_eq!(result.value, 7);
+        }
+
+        assert!(jit.stats.jit_compilations > 0);
+    }
+
+    #[test]
+    fn test_select_codegen_picks_arm64_backend_for_aarch64_target() {
+        let codegen = JitCompiler::select_codegen("aarch64");
+        assert_eq!(codegen.target_name(), "aarch64");
+    }
+
+    #[test]
+    fn test_select_codegen_defaults_to_x86_64_backend_for_other_targets() {
+        assert_eq!(JitCompiler::select_codegen("x86_64").target_name(), "x86-64");
+        assert_eq!(JitCompiler::select_codegen("wasm32").target_name(), "x86-64");
+    }
+
     #[test]
     fn test_variable_expressions() {
         let mut jit = JitCompiler::new();
@@ -386,4 +656,58 @@ mod tests {
         assert!(jit.stats.jit_compilations > 0);
         jit.print_detailed_stats();
     }
+
+    #[test]
+    fn test_boolean_comparison_is_jit_compiled() {
+        let mut jit = JitCompiler::new();
+
+        // JITのコード生成・実行は生の整数レジスタしか扱わないため、比較結果は
+        // インタープリタ実行時のように`Value::Bool`ではなく`Value::Int(0/1)`として
+        // 返ってくる。これは`ExecutableMemory`が型タグを持たないことに起因する
+        // 既知の制約（境界の型変換は常に`as_i64_lossy`の逆方向＝`Value::Int`固定）
+        let mut last = None;
+        for _ in 0..12 {
+            last = Some(jit.execute_string("5 > 3").unwrap());
+        }
+        let result = last.unwrap();
+        assert_eq!(result.value, 1);
+        assert!(jit.stats.jit_compilations > 0);
+        assert!(result.was_jit_compiled);
+    }
+
+    #[test]
+    fn test_fallback_expression_is_jit_compiled_when_variable_stays_unbound() {
+        let mut jit = JitCompiler::new();
+
+        // `unbound`は一度も代入されないので、ホットスポット化後も`resolve_fallbacks`は
+        // 毎回フォールバック側（`99`）を埋め込んだコードを生成するはず
+        let mut last = None;
+        for _ in 0..12 {
+            last = Some(jit.execute_string("unbound ?? 99").unwrap());
+        }
+        let result = last.unwrap();
+        assert_eq!(result.value, 99);
+        assert!(jit.stats.jit_compilations > 0);
+        assert!(result.was_jit_compiled);
+    }
+
+    #[test]
+    fn test_fallback_expression_is_jit_compiled_when_variable_is_bound() {
+        let mut jit = JitCompiler::new();
+        jit.execute_string("bound = 7").unwrap();
+
+        // `bound`は束縛済みなので、`resolve_fallbacks`はprimary側を埋め込んだコードを
+        // 生成し、フォールバックの`99`は一切使われないはず。このテストは
+        // `ExecutableMemory::execute`がJIT実行後の変数環境を正しく再構築できている
+        // ことにも依存している（誤って`bound`が壊れると2回目以降の`bound ?? 99`が
+        // 束縛済みと判定されなくなる）
+        let mut last = None;
+        for _ in 0..12 {
+            last = Some(jit.execute_string("bound ?? 99").unwrap());
+        }
+        let result = last.unwrap();
+        assert_eq!(result.value, 7);
+        assert!(jit.stats.jit_compilations > 0);
+        assert!(result.was_jit_compiled);
+    }
 }
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// トークンの種類
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TokenType {
     // リテラル
     Number(i64),
+    Float(f64),
+    Str(String),
     Identifier(String),
 
     // 演算子
@@ -20,6 +23,10 @@ pub enum TokenType {
     Greater,      // >
     LessEqual,    // <=
     GreaterEqual, // >=
+    Bang,         // !
+    AmpAmp,       // &&
+    PipePipe,     // ||
+    QuestionQuestion, // ??
 
     // デリミタ
     LeftParen,    // (
@@ -29,18 +36,74 @@ pub enum TokenType {
 
     // キーワード
     If,
+    While,
+    True,
+    False,
 
     // その他
     Whitespace,
+    Comment(String),
     EOF,
 }
 
+/// ソース上の範囲（`char`単位のオフセット、`[start, end)`）。バイトではなく文字数で
+/// 数えるのは、`Lexer`が内部で`Vec<char>`として走査しているのに合わせるため
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
 /// トークン
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token {
     pub token_type: TokenType,
     pub lexeme: String,
-    pub position: usize,
+    pub span: Span,
+}
+
+/// 字句解析中に発生するエラー。発生位置（バイトではなく文字オフセット）を保持する
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError {
+    UnexpectedChar { ch: char, position: usize },
+    UnterminatedString { position: usize },
+    UnterminatedComment { position: usize },
+    InvalidNumber { lexeme: String, position: usize },
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexError::UnexpectedChar { ch, position } => {
+                write!(f, "Unexpected character '{}' at position {}", ch, position)
+            }
+            LexError::UnterminatedString { position } => {
+                write!(f, "Unterminated string literal starting at position {}", position)
+            }
+            LexError::UnterminatedComment { position } => {
+                write!(f, "Unterminated block comment starting at position {}", position)
+            }
+            LexError::InvalidNumber { lexeme, position } => {
+                write!(f, "Invalid numeric literal '{}' at position {}", lexeme, position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LexError {}
+
+impl LexError {
+    /// 診断表示用の範囲。どのバリアントも発生位置を1点しか持たないため、
+    /// その文字を指す幅1の範囲として扱う
+    pub fn span(&self) -> Span {
+        let position = match self {
+            LexError::UnexpectedChar { position, .. } => *position,
+            LexError::UnterminatedString { position } => *position,
+            LexError::UnterminatedComment { position } => *position,
+            LexError::InvalidNumber { position, .. } => *position,
+        };
+        Span { start: position, end: position + 1 }
+    }
 }
 
 /// 字句解析器
@@ -48,6 +111,8 @@ pub struct Lexer {
     input: Vec<char>,
     current: usize,
     position: usize,
+    /// trueの場合、空白・コメントを読み飛ばさず `Whitespace`/`Comment` トークンとして返す
+    include_trivia: bool,
 }
 
 impl Lexer {
@@ -56,19 +121,35 @@ impl Lexer {
             input: input.chars().collect(),
             current: 0,
             position: 0,
+            include_trivia: false,
+        }
+    }
+
+    /// 空白・コメントをトークンとして保持するレキサーを作成する
+    /// （フォーマッタやシンタックスハイライタなど、原文を復元したいツール向け）
+    pub fn new_with_trivia(input: &str) -> Self {
+        Self {
+            include_trivia: true,
+            ..Self::new(input)
         }
     }
 
     /// 次のトークンを取得
-    pub fn next_token(&mut self) -> Token {
-        self.skip_whitespace();
+    pub fn next_token(&mut self) -> Result<Token, LexError> {
+        if self.include_trivia {
+            if let Some(token) = self.try_read_trivia()? {
+                return Ok(token);
+            }
+        } else {
+            self.skip_trivia()?;
+        }
 
         if self.is_at_end() {
-            return Token {
+            return Ok(Token {
                 token_type: TokenType::EOF,
                 lexeme: String::new(),
-                position: self.position,
-            };
+                span: Span { start: self.position, end: self.position },
+            });
         }
 
         let start_pos = self.position;
@@ -95,8 +176,28 @@ impl Lexer {
                 if self.match_char('=') {
                     TokenType::NotEqual
                 } else {
-                    // 単体の '!' はエラーとして扱う
-                    panic!("Unexpected character: !");
+                    TokenType::Bang
+                }
+            }
+            '&' => {
+                if self.match_char('&') {
+                    TokenType::AmpAmp
+                } else {
+                    return Err(LexError::UnexpectedChar { ch: '&', position: start_pos });
+                }
+            }
+            '|' => {
+                if self.match_char('|') {
+                    TokenType::PipePipe
+                } else {
+                    return Err(LexError::UnexpectedChar { ch: '|', position: start_pos });
+                }
+            }
+            '?' => {
+                if self.match_char('?') {
+                    TokenType::QuestionQuestion
+                } else {
+                    return Err(LexError::UnexpectedChar { ch: '?', position: start_pos });
                 }
             }
             '<' => {
@@ -113,35 +214,36 @@ impl Lexer {
                     TokenType::Greater
                 }
             }
-            _ if c.is_ascii_digit() => {
-                let number = self.read_number(c);
-                TokenType::Number(number)
-            }
+            '"' => TokenType::Str(self.read_string(start_pos)?),
+            _ if c.is_ascii_digit() => self.read_number(c, start_pos)?,
             _ if c.is_ascii_alphabetic() || c == '_' => {
                 let identifier = self.read_identifier(c);
                 match identifier.as_str() {
                     "if" => TokenType::If,
+                    "while" => TokenType::While,
+                    "true" => TokenType::True,
+                    "false" => TokenType::False,
                     _ => TokenType::Identifier(identifier),
                 }
             }
-            _ => panic!("Unexpected character: {}", c),
+            _ => return Err(LexError::UnexpectedChar { ch: c, position: start_pos }),
         };
 
         let lexeme = self.input[start_pos..self.position].iter().collect();
 
-        Token {
+        Ok(Token {
             token_type,
             lexeme,
-            position: start_pos,
-        }
+            span: Span { start: start_pos, end: self.position },
+        })
     }
 
     /// すべてのトークンを取得
-    pub fn tokenize(&mut self) -> Vec<Token> {
+    pub fn tokenize(&mut self) -> Result<Vec<Token>, LexError> {
         let mut tokens = Vec::new();
 
         loop {
-            let token = self.next_token();
+            let token = self.next_token()?;
             let is_eof = matches!(token.token_type, TokenType::EOF);
             tokens.push(token);
 
@@ -150,7 +252,7 @@ impl Lexer {
             }
         }
 
-        tokens
+        Ok(tokens)
     }
 
     fn is_at_end(&self) -> bool {
@@ -172,6 +274,20 @@ impl Lexer {
         }
     }
 
+    fn peek_next(&self) -> char {
+        if self.current + 1 >= self.input.len() {
+            '\0'
+        } else {
+            self.input[self.current + 1]
+        }
+    }
+
+    /// `current`から`offset`文字先を覗き見る（指数部の符号込みで2文字先まで先読みしたい
+    /// `read_number`専用。範囲外なら`peek`/`peek_next`と同様`'\0'`を返す）
+    fn peek_at(&self, offset: usize) -> char {
+        self.input.get(self.current + offset).copied().unwrap_or('\0')
+    }
+
     fn match_char(&mut self, expected: char) -> bool {
         if self.is_at_end() || self.input[self.current] != expected {
             false
@@ -182,21 +298,221 @@ impl Lexer {
         }
     }
 
-    fn skip_whitespace(&mut self) {
-        while !self.is_at_end() && self.peek().is_whitespace() {
+    /// 空白と行/ブロックコメントをまとめて読み飛ばす（非trivia用）
+    fn skip_trivia(&mut self) -> Result<(), LexError> {
+        loop {
+            if !self.is_at_end() && self.peek().is_whitespace() {
+                self.advance();
+                continue;
+            }
+            if self.at_line_comment_start() {
+                self.skip_line_comment();
+                continue;
+            }
+            if self.at_block_comment_start() {
+                let start_pos = self.position;
+                self.skip_block_comment(start_pos)?;
+                continue;
+            }
+            break;
+        }
+        Ok(())
+    }
+
+    /// 次のトークンが空白またはコメントなら、原文を保持したトークンとして返す
+    fn try_read_trivia(&mut self) -> Result<Option<Token>, LexError> {
+        if self.is_at_end() {
+            return Ok(None);
+        }
+
+        let start_pos = self.position;
+
+        if self.peek().is_whitespace() {
+            while !self.is_at_end() && self.peek().is_whitespace() {
+                self.advance();
+            }
+            let lexeme: String = self.input[start_pos..self.position].iter().collect();
+            let span = Span { start: start_pos, end: self.position };
+            return Ok(Some(Token { token_type: TokenType::Whitespace, lexeme, span }));
+        }
+
+        if self.at_line_comment_start() {
+            self.skip_line_comment();
+            let lexeme: String = self.input[start_pos..self.position].iter().collect();
+            let span = Span { start: start_pos, end: self.position };
+            return Ok(Some(Token { token_type: TokenType::Comment(lexeme.clone()), lexeme, span }));
+        }
+
+        if self.at_block_comment_start() {
+            self.skip_block_comment(start_pos)?;
+            let lexeme: String = self.input[start_pos..self.position].iter().collect();
+            let span = Span { start: start_pos, end: self.position };
+            return Ok(Some(Token { token_type: TokenType::Comment(lexeme.clone()), lexeme, span }));
+        }
+
+        Ok(None)
+    }
+
+    fn at_line_comment_start(&self) -> bool {
+        !self.is_at_end() && self.peek() == '/' && self.peek_next() == '/'
+    }
+
+    fn at_block_comment_start(&self) -> bool {
+        !self.is_at_end() && self.peek() == '/' && self.peek_next() == '*'
+    }
+
+    /// `//` から行末（改行の手前）までを読み飛ばす
+    fn skip_line_comment(&mut self) {
+        while !self.is_at_end() && self.peek() != '\n' {
             self.advance();
         }
     }
 
-    fn read_number(&mut self, first_digit: char) -> i64 {
+    /// `/*` から対応する `*/` までを読み飛ばす
+    fn skip_block_comment(&mut self, start_pos: usize) -> Result<(), LexError> {
+        self.advance(); // '/'
+        self.advance(); // '*'
+
+        loop {
+            if self.is_at_end() {
+                return Err(LexError::UnterminatedComment { position: start_pos });
+            }
+            if self.peek() == '*' && self.peek_next() == '/' {
+                self.advance(); // '*'
+                self.advance(); // '/'
+                break;
+            }
+            self.advance();
+        }
+
+        Ok(())
+    }
+
+    fn read_number(&mut self, first_digit: char, start_pos: usize) -> Result<TokenType, LexError> {
+        // 0x / 0b 接頭辞は基数付きリテラルとして扱う
+        if first_digit == '0' && (self.peek() == 'x' || self.peek() == 'X') {
+            self.advance(); // 'x'/'X'
+            return Ok(TokenType::Number(
+                self.read_radix_literal(16, |c| c.is_ascii_hexdigit(), start_pos)?,
+            ));
+        }
+        if first_digit == '0' && (self.peek() == 'b' || self.peek() == 'B') {
+            self.advance(); // 'b'/'B'
+            return Ok(TokenType::Number(
+                self.read_radix_literal(2, |c| c == '0' || c == '1', start_pos)?,
+            ));
+        }
+
         let mut number_str = String::new();
         number_str.push(first_digit);
 
-        while !self.is_at_end() && self.peek().is_ascii_digit() {
+        while !self.is_at_end() && (self.peek().is_ascii_digit() || self.peek() == '_') {
             number_str.push(self.advance());
         }
 
-        number_str.parse().unwrap_or(0)
+        // 小数点の後に数字が続く場合のみ浮動小数点として扱う（メソッド呼び出しの"."と区別）
+        let mut is_float = false;
+        if !self.is_at_end() && self.peek() == '.' && self.peek_next().is_ascii_digit() {
+            is_float = true;
+            number_str.push(self.advance()); // '.'
+            while !self.is_at_end() && (self.peek().is_ascii_digit() || self.peek() == '_') {
+                number_str.push(self.advance());
+            }
+        }
+
+        // 指数部（`1e9`, `2.5e-3`）。符号はオプションだが、その直後に数字が続く場合のみ
+        // 指数部として受理する（`1e`や`1e+`で終わる識別子っぽい入力と区別するため）
+        if !self.is_at_end() && (self.peek() == 'e' || self.peek() == 'E') {
+            let has_sign = self.peek_next() == '+' || self.peek_next() == '-';
+            let digit_after_sign = if has_sign { self.peek_at(2) } else { self.peek_next() };
+            if digit_after_sign.is_ascii_digit() {
+                is_float = true;
+                number_str.push(self.advance()); // 'e'/'E'
+                if has_sign {
+                    number_str.push(self.advance()); // '+'/'-'
+                }
+                while !self.is_at_end() && (self.peek().is_ascii_digit() || self.peek() == '_') {
+                    number_str.push(self.advance());
+                }
+            }
+        }
+
+        let cleaned: String = number_str.chars().filter(|&c| c != '_').collect();
+        if is_float {
+            cleaned.parse().map(TokenType::Float).map_err(|_| LexError::InvalidNumber {
+                lexeme: number_str,
+                position: start_pos,
+            })
+        } else {
+            if cleaned.is_empty() {
+                return Err(LexError::InvalidNumber { lexeme: number_str, position: start_pos });
+            }
+            cleaned.parse().map(TokenType::Number).map_err(|_| LexError::InvalidNumber {
+                lexeme: number_str,
+                position: start_pos,
+            })
+        }
+    }
+
+    /// `0x`/`0b` の後に続く基数付き桁列を読み取り、`_` 区切りを取り除いてパースする
+    fn read_radix_literal(
+        &mut self,
+        radix: u32,
+        is_digit: impl Fn(char) -> bool,
+        start_pos: usize,
+    ) -> Result<i64, LexError> {
+        let mut digits = String::new();
+
+        while !self.is_at_end() && (is_digit(self.peek()) || self.peek() == '_') {
+            digits.push(self.advance());
+        }
+
+        let cleaned: String = digits.chars().filter(|&c| c != '_').collect();
+        if cleaned.is_empty() {
+            return Err(LexError::InvalidNumber { lexeme: digits, position: start_pos });
+        }
+
+        i64::from_str_radix(&cleaned, radix).map_err(|_| LexError::InvalidNumber {
+            lexeme: cleaned,
+            position: start_pos,
+        })
+    }
+
+    /// 文字列リテラルを読み取る（開始の `"` は読み込み済み）
+    fn read_string(&mut self, start_pos: usize) -> Result<String, LexError> {
+        let mut value = String::new();
+
+        loop {
+            if self.is_at_end() {
+                return Err(LexError::UnterminatedString { position: start_pos });
+            }
+
+            let c = self.advance();
+            match c {
+                '"' => break,
+                '\\' => {
+                    if self.is_at_end() {
+                        return Err(LexError::UnterminatedString { position: start_pos });
+                    }
+                    let escaped = self.advance();
+                    match escaped {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        '\\' => value.push('\\'),
+                        '"' => value.push('"'),
+                        _ => {
+                            return Err(LexError::UnexpectedChar {
+                                ch: escaped,
+                                position: self.position - 1,
+                            })
+                        }
+                    }
+                }
+                other => value.push(other),
+            }
+        }
+
+        Ok(value)
     }
 
     fn read_identifier(&mut self, first_char: char) -> String {
@@ -218,7 +534,7 @@ mod tests {
     #[test]
     fn test_basic_arithmetic() {
         let mut lexer = Lexer::new("1 + 2 * 3");
-        let tokens = lexer.tokenize();
+        let tokens = lexer.tokenize().unwrap();
 
         assert_eq!(tokens.len(), 6); // 1, +, 2, *, 3, EOF
         assert_eq!(tokens[0].token_type, TokenType::Number(1));
@@ -232,21 +548,194 @@ mod tests {
     #[test]
     fn test_variables_and_assignment() {
         let mut lexer = Lexer::new("x = 42");
-        let tokens = lexer.tokenize();
+        let tokens = lexer.tokenize().unwrap();
 
         assert_eq!(tokens[0].token_type, TokenType::Identifier("x".to_string()));
         assert_eq!(tokens[1].token_type, TokenType::Equal);
         assert_eq!(tokens[2].token_type, TokenType::Number(42));
     }
 
+    #[test]
+    fn test_logical_operators() {
+        let mut lexer = Lexer::new("!a && b || c");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Bang);
+        assert_eq!(tokens[1].token_type, TokenType::Identifier("a".to_string()));
+        assert_eq!(tokens[2].token_type, TokenType::AmpAmp);
+        assert_eq!(tokens[3].token_type, TokenType::Identifier("b".to_string()));
+        assert_eq!(tokens[4].token_type, TokenType::PipePipe);
+        assert_eq!(tokens[5].token_type, TokenType::Identifier("c".to_string()));
+    }
+
+    #[test]
+    fn test_nullish_coalescing_operator() {
+        let mut lexer = Lexer::new("x ?? 5");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Identifier("x".to_string()));
+        assert_eq!(tokens[1].token_type, TokenType::QuestionQuestion);
+        assert_eq!(tokens[2].token_type, TokenType::Number(5));
+    }
+
+    #[test]
+    fn test_lone_question_mark_is_an_error() {
+        let mut lexer = Lexer::new("x ? 5");
+        assert!(lexer.tokenize().is_err());
+    }
+
+    #[test]
+    fn test_float_literal() {
+        // `clippy::approx_constant`が`3.14`をπの近似値とみなして警告するため、
+        // 定数と紛らわしくないリテラルを使う
+        let mut lexer = Lexer::new("3.25 + 1");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Float(3.25));
+        assert_eq!(tokens[1].token_type, TokenType::Plus);
+        assert_eq!(tokens[2].token_type, TokenType::Number(1));
+    }
+
+    #[test]
+    fn test_exponent_literal_without_decimal_point_is_a_float() {
+        let mut lexer = Lexer::new("1e9");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Float(1e9));
+    }
+
+    #[test]
+    fn test_exponent_literal_with_decimal_point_and_negative_sign() {
+        let mut lexer = Lexer::new("2.5e-3");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Float(2.5e-3));
+    }
+
+    #[test]
+    fn test_trailing_e_without_digits_is_not_consumed_as_an_exponent() {
+        // `1e`の後に数字が続かないため、指数部ではなく整数リテラル`1`と識別子`e`に分かれる
+        let mut lexer = Lexer::new("1e + 2");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Number(1));
+        assert_eq!(tokens[1].token_type, TokenType::Identifier("e".to_string()));
+    }
+
+    #[test]
+    fn test_hex_binary_and_underscore_literals() {
+        let mut lexer = Lexer::new("0xFF_FF 0b1010 1_000_000");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Number(0xFFFF));
+        assert_eq!(tokens[1].token_type, TokenType::Number(0b1010));
+        assert_eq!(tokens[2].token_type, TokenType::Number(1_000_000));
+    }
+
+    #[test]
+    fn test_boolean_keywords() {
+        let mut lexer = Lexer::new("true && false");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::True);
+        assert_eq!(tokens[1].token_type, TokenType::AmpAmp);
+        assert_eq!(tokens[2].token_type, TokenType::False);
+    }
+
+    #[test]
+    fn test_malformed_hex_literal_is_an_error() {
+        let mut lexer = Lexer::new("0x");
+        let err = lexer.tokenize().unwrap_err();
+        assert!(matches!(err, LexError::InvalidNumber { .. }));
+    }
+
+    #[test]
+    fn test_string_literal_with_escapes() {
+        let mut lexer = Lexer::new(r#""hello\nworld\"\\" "#);
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Str("hello\nworld\"\\".to_string()));
+    }
+
+    #[test]
+    fn test_unterminated_string_reports_position() {
+        let mut lexer = Lexer::new("\"abc");
+        let err = lexer.tokenize().unwrap_err();
+        assert_eq!(err, LexError::UnterminatedString { position: 0 });
+    }
+
+    #[test]
+    fn test_unexpected_char_reports_position() {
+        let mut lexer = Lexer::new("1 @ 2");
+        let err = lexer.tokenize().unwrap_err();
+        assert_eq!(err, LexError::UnexpectedChar { ch: '@', position: 2 });
+    }
+
+    #[test]
+    fn test_line_and_block_comments_are_skipped() {
+        let mut lexer = Lexer::new("1 // this is a comment\n + /* inline */ 2");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::Number(1));
+        assert_eq!(tokens[1].token_type, TokenType::Plus);
+        assert_eq!(tokens[2].token_type, TokenType::Number(2));
+        assert_eq!(tokens[3].token_type, TokenType::EOF);
+    }
+
+    #[test]
+    fn test_unterminated_block_comment_is_an_error() {
+        let mut lexer = Lexer::new("1 /* oops");
+        let err = lexer.tokenize().unwrap_err();
+        assert!(matches!(err, LexError::UnterminatedComment { .. }));
+    }
+
+    #[test]
+    fn test_trivia_mode_preserves_whitespace_and_comments() {
+        let source = "x = 1 // set x\n";
+        let mut lexer = Lexer::new_with_trivia(source);
+        let tokens = lexer.tokenize().unwrap();
+
+        // トークンのlexemeを連結すれば原文を完全に再構成できる
+        let reconstructed: String = tokens
+            .iter()
+            .filter(|t| t.token_type != TokenType::EOF)
+            .map(|t| t.lexeme.as_str())
+            .collect();
+        assert_eq!(reconstructed, source);
+
+        assert!(tokens.iter().any(|t| t.token_type == TokenType::Whitespace));
+        assert!(tokens
+            .iter()
+            .any(|t| matches!(&t.token_type, TokenType::Comment(c) if c == "// set x")));
+    }
+
+    #[test]
+    fn test_while_keyword_and_semicolon() {
+        let mut lexer = Lexer::new("while(i < 10, i = i + 1; sum = sum + i)");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].token_type, TokenType::While);
+        assert!(tokens.iter().any(|t| t.token_type == TokenType::Semicolon));
+    }
+
+    #[test]
+    fn test_token_span_covers_lexeme() {
+        let mut lexer = Lexer::new("x = 42");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0].span, Span { start: 0, end: 1 }); // "x"
+        assert_eq!(tokens[1].span, Span { start: 2, end: 3 }); // "="
+        assert_eq!(tokens[2].span, Span { start: 4, end: 6 }); // "42"
+    }
+
     #[test]
     fn test_function_call() {
         let mut lexer = Lexer::new("fib(10)");
-        let tokens = lexer.tokenize();
+        let tokens = lexer.tokenize().unwrap();
 
         assert_eq!(tokens[0].token_type, TokenType::Identifier("fib".to_string()));
         assert_eq!(tokens[1].token_type, TokenType::LeftParen);
         assert_eq!(tokens[2].token_type, TokenType::Number(10));
         assert_eq!(tokens[3].token_type, TokenType::RightParen);
     }
-}
\ No newline at end of file
+}
@@ -4,6 +4,8 @@ mod parser;
 mod interpreter;
 mod jit;
 mod api;
+mod conformance;
+mod lsp;
 
 use ast::*;
 use interpreter::Interpreter;
@@ -18,6 +20,12 @@ fn main() -> Result<()> {
     if args.len() > 1 && args[1] == "server" {
         // Webサーバーモード
         start_web_server()
+    } else if args.len() > 1 && args[1] == "conformance" {
+        // tests/cases/のファイル駆動コンフォーマンステストを実行
+        conformance::run_cli()
+    } else if args.len() > 1 && args[1] == "lsp" {
+        // 標準入出力上でLSP（Language Server Protocol）を話すモード
+        lsp::run_stdio()
     } else {
         // テストモード（従来の動作）
         run_tests()
@@ -84,7 +92,11 @@ fn test_lexer_parser_interpreter() -> Result<()> {
     let mut parser = Parser::new("x = 42")?;
     let expr = parser.parse()?;
     let result = interpreter.evaluate(&expr)?;
-    println!("   結果: {} (変数x = {})", result.value, result.environment.get("x").unwrap_or(&0));
+    println!(
+        "   結果: {} (変数x = {})",
+        result.value,
+        result.environment.get("x").unwrap_or(&Value::Int(0))
+    );
     assert_eq!(result.value, 42);
 
     // テストケース3: 変数を使った演算
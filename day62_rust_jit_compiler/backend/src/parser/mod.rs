@@ -1,22 +1,103 @@
-use crate::ast::{BinaryOp, Expr};
-use crate::lexer::{Lexer, Token, TokenType};
-use anyhow::{anyhow, Result};
+use crate::ast::{BinaryOp, Expr, LogicalOp, UnaryOp};
+use crate::lexer::{Lexer, Span, Token, TokenType};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::Discriminant;
+
+/// 構文解析中に発生するエラー。LSPの診断（`textDocument/publishDiagnostics`）が
+/// 精密な範囲を報告できるよう、エラー発生時点のトークンの`Span`を保持する
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// 前置パース関数（数値・識別子・括弧・単項演算子など）
+type PrefixFn = fn(&mut Parser) -> Result<Expr>;
 
-/// 構文解析器
+/// 中置パース関数（二項演算子・呼び出し演算子など）。
+/// 左側に既に解析済みの式を受け取り、続きを解析して返す。
+type InfixFn = fn(&mut Parser, Expr) -> Result<Expr>;
+
+/// 束縛力（binding power）。大きいほど強く結合する。
+type BindingPower = u8;
+
+// `??`はshellの`${VAR:-fallback}`に倣い、他のどの演算子よりも緩く結合する
+// （`a || b ?? c`は`(a || b) ?? c`になってほしいため、論理ORよりさらに弱い）
+const BP_NULLISH: BindingPower = 1;
+const BP_LOGICAL_OR: BindingPower = 2;
+const BP_LOGICAL_AND: BindingPower = 3;
+const BP_EQUALITY: BindingPower = 4;
+const BP_COMPARISON: BindingPower = 5;
+const BP_TERM: BindingPower = 6;
+const BP_FACTOR: BindingPower = 7;
+const BP_UNARY: BindingPower = 8;
+const BP_CALL: BindingPower = 9;
+
+/// 構文解析器（Pratt / 優先順位climbing方式）
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    prefix_fns: HashMap<Discriminant<TokenType>, PrefixFn>,
+    infix_fns: HashMap<Discriminant<TokenType>, (BindingPower, InfixFn)>,
 }
 
 impl Parser {
     pub fn new(input: &str) -> Result<Self> {
         let mut lexer = Lexer::new(input);
-        let tokens = lexer.tokenize();
+        let tokens = lexer.tokenize()?;
 
-        Ok(Self {
+        let mut parser = Self {
             tokens,
             current: 0,
-        })
+            prefix_fns: HashMap::new(),
+            infix_fns: HashMap::new(),
+        };
+        parser.register_rules();
+        Ok(parser)
+    }
+
+    /// 前置・中置パース関数を `TokenType` の判別子ごとに登録する
+    fn register_rules(&mut self) {
+        self.prefix_fns.insert(discr(&TokenType::Number(0)), Parser::parse_number);
+        self.prefix_fns.insert(discr(&TokenType::Float(0.0)), Parser::parse_float);
+        self.prefix_fns.insert(discr(&TokenType::Str(String::new())), Parser::parse_string);
+        self.prefix_fns.insert(discr(&TokenType::Identifier(String::new())), Parser::parse_identifier);
+        self.prefix_fns.insert(discr(&TokenType::LeftParen), Parser::parse_grouping);
+        self.prefix_fns.insert(discr(&TokenType::If), Parser::parse_if);
+        self.prefix_fns.insert(discr(&TokenType::While), Parser::parse_while);
+        self.prefix_fns.insert(discr(&TokenType::True), Parser::parse_bool);
+        self.prefix_fns.insert(discr(&TokenType::False), Parser::parse_bool);
+        self.prefix_fns.insert(discr(&TokenType::Minus), Parser::parse_unary_minus);
+        self.prefix_fns.insert(discr(&TokenType::Bang), Parser::parse_not);
+
+        let mut infix = |token_type: TokenType, bp: BindingPower, f: InfixFn| {
+            self.infix_fns.insert(discr(&token_type), (bp, f));
+        };
+        infix(TokenType::QuestionQuestion, BP_NULLISH, Parser::parse_nullish);
+        infix(TokenType::PipePipe, BP_LOGICAL_OR, Parser::parse_logical);
+        infix(TokenType::AmpAmp, BP_LOGICAL_AND, Parser::parse_logical);
+        infix(TokenType::EqualEqual, BP_EQUALITY, Parser::parse_binary);
+        infix(TokenType::NotEqual, BP_EQUALITY, Parser::parse_binary);
+        infix(TokenType::Greater, BP_COMPARISON, Parser::parse_binary);
+        infix(TokenType::GreaterEqual, BP_COMPARISON, Parser::parse_binary);
+        infix(TokenType::Less, BP_COMPARISON, Parser::parse_binary);
+        infix(TokenType::LessEqual, BP_COMPARISON, Parser::parse_binary);
+        infix(TokenType::Plus, BP_TERM, Parser::parse_binary);
+        infix(TokenType::Minus, BP_TERM, Parser::parse_binary);
+        infix(TokenType::Star, BP_FACTOR, Parser::parse_binary);
+        infix(TokenType::Slash, BP_FACTOR, Parser::parse_binary);
+        infix(TokenType::Percent, BP_FACTOR, Parser::parse_binary);
+        infix(TokenType::LeftParen, BP_CALL, Parser::parse_call);
     }
 
     /// 式を解析してASTを生成
@@ -29,204 +110,262 @@ impl Parser {
         self.assignment()
     }
 
-    /// assignment → IDENTIFIER "=" assignment | logical_or
+    /// assignment → IDENTIFIER "=" assignment | parse_expression(0)
     fn assignment(&mut self) -> Result<Expr> {
-        let expr = self.logical_or()?;
-
-        if self.match_token(&TokenType::Equal) {
-            if let Expr::Variable(name) = expr {
+        // 代入かどうかを判定するため、identifierの次に "=" が続くかを覗き見る
+        if let TokenType::Identifier(_) = self.peek().token_type {
+            if let TokenType::Equal = self.peek_at(1).map(|t| &t.token_type).unwrap_or(&TokenType::EOF) {
+                let name = match self.advance().unwrap().token_type.clone() {
+                    TokenType::Identifier(name) => name,
+                    _ => unreachable!(),
+                };
+                self.advance(); // consume '='
                 let value = Box::new(self.assignment()?);
                 return Ok(Expr::Assignment { name, value });
-            } else {
-                return Err(anyhow!("Invalid assignment target"));
             }
         }
 
-        Ok(expr)
+        self.parse_expression(0)
     }
 
-    /// logical_or → logical_and ( "||" logical_and )*
-    /// (今回は論理演算子は未実装、将来の拡張用)
-    fn logical_or(&mut self) -> Result<Expr> {
-        self.logical_and()
+    /// Pratt / 優先順位climbingのコア: 最小束縛力`min_bp`より強く結合する限り中置演算子を消費する
+    fn parse_expression(&mut self, min_bp: BindingPower) -> Result<Expr> {
+        let prefix = self
+            .prefix_fns
+            .get(&discr(&self.peek().token_type))
+            .copied()
+            .ok_or_else(|| self.error_here(format!("Unexpected token: {:?}", self.peek())))?;
+
+        let mut left = prefix(self)?;
+
+        loop {
+            let next_discr = discr(&self.peek().token_type);
+            let Some(&(bp, infix)) = self.infix_fns.get(&next_discr) else {
+                break;
+            };
+            if bp <= min_bp {
+                break;
+            }
+            left = infix(self, left)?;
+        }
+
+        Ok(left)
     }
 
-    /// logical_and → equality ( "&&" equality )*
-    /// (今回は論理演算子は未実装、将来の拡張用)
-    fn logical_and(&mut self) -> Result<Expr> {
-        self.equality()
+    // === 前置パース関数 ===
+
+    fn parse_number(&mut self) -> Result<Expr> {
+        match self.advance().unwrap().token_type.clone() {
+            TokenType::Number(n) => Ok(Expr::Number(n)),
+            _ => unreachable!(),
+        }
     }
 
-    /// equality → comparison ( ( "!=" | "==" ) comparison )*
-    fn equality(&mut self) -> Result<Expr> {
-        let mut expr = self.comparison()?;
+    fn parse_float(&mut self) -> Result<Expr> {
+        match self.advance().unwrap().token_type.clone() {
+            TokenType::Float(f) => Ok(Expr::Float(f)),
+            _ => unreachable!(),
+        }
+    }
 
-        while self.match_tokens(&[TokenType::NotEqual, TokenType::EqualEqual]) {
-            let op = match self.previous().token_type {
-                TokenType::NotEqual => BinaryOp::NotEqual,
-                TokenType::EqualEqual => BinaryOp::Equal,
-                _ => unreachable!(),
-            };
-            let right = Box::new(self.comparison()?);
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                op,
-                right,
-            };
+    fn parse_bool(&mut self) -> Result<Expr> {
+        match self.advance().unwrap().token_type.clone() {
+            TokenType::True => Ok(Expr::Bool(true)),
+            TokenType::False => Ok(Expr::Bool(false)),
+            _ => unreachable!(),
         }
+    }
 
-        Ok(expr)
+    fn parse_string(&mut self) -> Result<Expr> {
+        match self.advance().unwrap().token_type.clone() {
+            TokenType::Str(s) => Ok(Expr::Str(s)),
+            _ => unreachable!(),
+        }
     }
 
-    /// comparison → term ( ( ">" | ">=" | "<" | "<=" ) term )*
-    fn comparison(&mut self) -> Result<Expr> {
-        let mut expr = self.term()?;
-
-        while self.match_tokens(&[
-            TokenType::Greater,
-            TokenType::GreaterEqual,
-            TokenType::Less,
-            TokenType::LessEqual,
-        ]) {
-            let op = match self.previous().token_type {
-                TokenType::Greater => BinaryOp::Greater,
-                TokenType::GreaterEqual => BinaryOp::GreaterEq,
-                TokenType::Less => BinaryOp::Less,
-                TokenType::LessEqual => BinaryOp::LessEq,
-                _ => unreachable!(),
-            };
-            let right = Box::new(self.term()?);
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                op,
-                right,
-            };
+    fn parse_identifier(&mut self) -> Result<Expr> {
+        match self.advance().unwrap().token_type.clone() {
+            TokenType::Identifier(name) => Ok(Expr::Variable(name)),
+            _ => unreachable!(),
         }
+    }
 
+    fn parse_grouping(&mut self) -> Result<Expr> {
+        self.advance(); // consume '('
+        let expr = self.parse_expression(0)?;
+        if !self.match_token(&TokenType::RightParen) {
+            return Err(self.error_here("Expect ')' after expression"));
+        }
         Ok(expr)
     }
 
-    /// term → factor ( ( "-" | "+" ) factor )*
-    fn term(&mut self) -> Result<Expr> {
-        let mut expr = self.factor()?;
+    fn parse_unary_minus(&mut self) -> Result<Expr> {
+        self.advance(); // consume '-'
+        let operand = self.parse_expression(BP_UNARY)?;
+        Ok(Expr::Binary {
+            left: Box::new(Expr::Number(0)),
+            op: BinaryOp::Sub,
+            right: Box::new(operand),
+        })
+    }
+
+    fn parse_not(&mut self) -> Result<Expr> {
+        self.advance(); // consume '!'
+        let operand = Box::new(self.parse_expression(BP_UNARY)?);
+        Ok(Expr::Unary {
+            op: UnaryOp::Not,
+            operand,
+        })
+    }
 
-        while self.match_tokens(&[TokenType::Minus, TokenType::Plus]) {
-            let op = match self.previous().token_type {
-                TokenType::Minus => BinaryOp::Sub,
-                TokenType::Plus => BinaryOp::Add,
-                _ => unreachable!(),
-            };
-            let right = Box::new(self.factor()?);
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                op,
-                right,
-            };
+    fn parse_if(&mut self) -> Result<Expr> {
+        self.advance(); // consume 'if'
+
+        if !self.match_token(&TokenType::LeftParen) {
+            return Err(self.error_here("Expect '(' after 'if'"));
         }
 
-        Ok(expr)
-    }
+        let condition = Box::new(self.parse_expression(0)?);
 
-    /// factor → unary ( ( "/" | "*" | "%" ) unary )*
-    fn factor(&mut self) -> Result<Expr> {
-        let mut expr = self.unary()?;
+        if !self.match_token(&TokenType::Comma) {
+            return Err(self.error_here("Expect ',' after if condition"));
+        }
 
-        while self.match_tokens(&[TokenType::Slash, TokenType::Star, TokenType::Percent]) {
-            let op = match self.previous().token_type {
-                TokenType::Slash => BinaryOp::Div,
-                TokenType::Star => BinaryOp::Mul,
-                TokenType::Percent => BinaryOp::Mod,
-                _ => unreachable!(),
-            };
-            let right = Box::new(self.unary()?);
-            expr = Expr::Binary {
-                left: Box::new(expr),
-                op,
-                right,
-            };
+        let true_expr = Box::new(self.parse_expression(0)?);
+
+        if !self.match_token(&TokenType::Comma) {
+            return Err(self.error_here("Expect ',' after true expression"));
         }
 
-        Ok(expr)
-    }
+        let false_expr = Box::new(self.parse_expression(0)?);
 
-    /// unary → ( "!" | "-" ) unary | call
-    fn unary(&mut self) -> Result<Expr> {
-        if self.match_token(&TokenType::Minus) {
-            let expr = self.unary()?;
-            return Ok(Expr::Binary {
-                left: Box::new(Expr::Number(0)),
-                op: BinaryOp::Sub,
-                right: Box::new(expr),
-            });
+        if !self.match_token(&TokenType::RightParen) {
+            return Err(self.error_here("Expect ')' after if expression"));
         }
 
-        self.call()
+        Ok(Expr::If {
+            condition,
+            true_expr,
+            false_expr,
+        })
     }
 
-    /// call → primary ( "(" arguments? ")" )*
-    fn call(&mut self) -> Result<Expr> {
-        let mut expr = self.primary()?;
+    /// while(condition, body) をパースする。bodyは`;`区切りの文の列を許すため、
+    /// 一般の式（`parse_expression(0)`）ではなく`assignment()`を介して読む
+    fn parse_while(&mut self) -> Result<Expr> {
+        self.advance(); // consume 'while'
 
-        while self.match_token(&TokenType::LeftParen) {
-            if let Expr::Variable(name) = expr {
-                let args = self.finish_call()?;
-                expr = Expr::FunctionCall { name, args };
-            } else {
-                return Err(anyhow!("Only identifiers can be called as functions"));
-            }
+        if !self.match_token(&TokenType::LeftParen) {
+            return Err(self.error_here("Expect '(' after 'while'"));
         }
 
-        Ok(expr)
-    }
+        let condition = Box::new(self.parse_expression(0)?);
 
-    /// primary → NUMBER | IDENTIFIER | "(" expression ")" | "if" "(" expression "," expression "," expression ")"
-    fn primary(&mut self) -> Result<Expr> {
-        if let Some(token) = self.advance() {
-            match &token.token_type {
-                TokenType::Number(n) => Ok(Expr::Number(*n)),
-                TokenType::Identifier(name) => Ok(Expr::Variable(name.clone())),
-                TokenType::LeftParen => {
-                    let expr = self.expression()?;
-                    if !self.match_token(&TokenType::RightParen) {
-                        return Err(anyhow!("Expect ')' after expression"));
-                    }
-                    Ok(expr)
-                }
-                TokenType::If => {
-                    // if(condition, true_expr, false_expr)
-                    if !self.match_token(&TokenType::LeftParen) {
-                        return Err(anyhow!("Expect '(' after 'if'"));
-                    }
+        if !self.match_token(&TokenType::Comma) {
+            return Err(self.error_here("Expect ',' after while condition"));
+        }
 
-                    let condition = Box::new(self.expression()?);
+        let body = Box::new(self.parse_sequence()?);
 
-                    if !self.match_token(&TokenType::Comma) {
-                        return Err(anyhow!("Expect ',' after if condition"));
-                    }
+        if !self.match_token(&TokenType::RightParen) {
+            return Err(self.error_here("Expect ')' after while body"));
+        }
 
-                    let true_expr = Box::new(self.expression()?);
+        Ok(Expr::While { condition, body })
+    }
 
-                    if !self.match_token(&TokenType::Comma) {
-                        return Err(anyhow!("Expect ',' after true expression"));
-                    }
+    /// `;` 区切りの文の列をパースする。1文しかなければそのまま返し、
+    /// 2文以上なら`Expr::Sequence`でまとめる
+    fn parse_sequence(&mut self) -> Result<Expr> {
+        let mut statements = vec![self.assignment()?];
 
-                    let false_expr = Box::new(self.expression()?);
+        while self.match_token(&TokenType::Semicolon) {
+            statements.push(self.assignment()?);
+        }
 
-                    if !self.match_token(&TokenType::RightParen) {
-                        return Err(anyhow!("Expect ')' after if expression"));
-                    }
+        if statements.len() == 1 {
+            Ok(statements.into_iter().next().unwrap())
+        } else {
+            Ok(Expr::Sequence(statements))
+        }
+    }
 
-                    Ok(Expr::If {
-                        condition,
-                        true_expr,
-                        false_expr,
-                    })
-                }
-                _ => Err(anyhow!("Unexpected token: {:?}", token)),
+    // === 中置パース関数 ===
+
+    fn parse_binary(&mut self, left: Expr) -> Result<Expr> {
+        let op_token = self.advance().unwrap().token_type.clone();
+        let (op, bp) = match op_token {
+            TokenType::Plus => (BinaryOp::Add, BP_TERM),
+            TokenType::Minus => (BinaryOp::Sub, BP_TERM),
+            TokenType::Star => (BinaryOp::Mul, BP_FACTOR),
+            TokenType::Slash => (BinaryOp::Div, BP_FACTOR),
+            TokenType::Percent => (BinaryOp::Mod, BP_FACTOR),
+            TokenType::EqualEqual => (BinaryOp::Equal, BP_EQUALITY),
+            TokenType::NotEqual => (BinaryOp::NotEqual, BP_EQUALITY),
+            TokenType::Less => (BinaryOp::Less, BP_COMPARISON),
+            TokenType::Greater => (BinaryOp::Greater, BP_COMPARISON),
+            TokenType::LessEqual => (BinaryOp::LessEq, BP_COMPARISON),
+            TokenType::GreaterEqual => (BinaryOp::GreaterEq, BP_COMPARISON),
+            _ => unreachable!(),
+        };
+
+        // 左結合: 同じ優先順位の演算子が続けて束縛されないよう bp をそのまま最小束縛力に使う
+        let right = Box::new(self.parse_expression(bp)?);
+        Ok(Expr::Binary {
+            left: Box::new(left),
+            op,
+            right,
+        })
+    }
+
+    fn parse_logical(&mut self, left: Expr) -> Result<Expr> {
+        let op_token = self.advance().unwrap().token_type.clone();
+        let (op, bp) = match op_token {
+            TokenType::AmpAmp => (LogicalOp::And, BP_LOGICAL_AND),
+            TokenType::PipePipe => (LogicalOp::Or, BP_LOGICAL_OR),
+            _ => unreachable!(),
+        };
+
+        let right = Box::new(self.parse_expression(bp)?);
+        Ok(Expr::Logical {
+            left: Box::new(left),
+            op,
+            right,
+        })
+    }
+
+    /// `primary ?? fallback`: `primary`が未束縛の変数であれば`fallback`を返す
+    fn parse_nullish(&mut self, left: Expr) -> Result<Expr> {
+        self.advance(); // consume '??'
+        let fallback = Box::new(self.parse_expression(BP_NULLISH)?);
+        Ok(Expr::Fallback {
+            primary: Box::new(left),
+            fallback,
+        })
+    }
+
+    fn parse_call(&mut self, left: Expr) -> Result<Expr> {
+        self.advance(); // consume '('
+        let name = match left {
+            Expr::Variable(name) => name,
+            _ => return Err(self.error_here("Only identifiers can be called as functions")),
+        };
+        let args = self.finish_call()?;
+
+        // `default(x, fallback)`は`x ?? fallback`の関数呼び出し構文版。通常の
+        // `FunctionCall`として扱うと引数が先に全部評価されてしまい、fallbackの
+        // 遅延評価という要件を満たせないため、ここで`Expr::Fallback`へ変換する
+        if name == "default" {
+            if args.len() != 2 {
+                return Err(self.error_here("Expect 'default(expr, fallback)' with exactly two arguments"));
             }
-        } else {
-            Err(anyhow!("Unexpected end of input"))
+            let mut args = args.into_iter();
+            let primary = Box::new(args.next().unwrap());
+            let fallback = Box::new(args.next().unwrap());
+            return Ok(Expr::Fallback { primary, fallback });
         }
+
+        Ok(Expr::FunctionCall { name, args })
     }
 
     fn finish_call(&mut self) -> Result<Vec<Expr>> {
@@ -234,7 +373,7 @@ impl Parser {
 
         if !self.check(&TokenType::RightParen) {
             loop {
-                args.push(self.expression()?);
+                args.push(self.parse_expression(0)?);
                 if !self.match_token(&TokenType::Comma) {
                     break;
                 }
@@ -242,12 +381,14 @@ impl Parser {
         }
 
         if !self.match_token(&TokenType::RightParen) {
-            return Err(anyhow!("Expect ')' after arguments"));
+            return Err(self.error_here("Expect ')' after arguments"));
         }
 
         Ok(args)
     }
 
+    // === トークン操作ヘルパー ===
+
     fn match_token(&mut self, token_type: &TokenType) -> bool {
         if self.check(token_type) {
             self.advance();
@@ -257,16 +398,6 @@ impl Parser {
         }
     }
 
-    fn match_tokens(&mut self, token_types: &[TokenType]) -> bool {
-        for token_type in token_types {
-            if self.check(token_type) {
-                self.advance();
-                return true;
-            }
-        }
-        false
-    }
-
     fn check(&self, token_type: &TokenType) -> bool {
         if self.is_at_end() {
             false
@@ -290,8 +421,8 @@ impl Parser {
         &self.tokens[self.current]
     }
 
-    fn previous(&self) -> &Token {
-        &self.tokens[self.current - 1]
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.current + offset)
     }
 
     fn previous_option(&self) -> Option<&Token> {
@@ -301,6 +432,15 @@ impl Parser {
             None
         }
     }
+
+    /// 現在のトークンの位置に紐づく`ParseError`を`anyhow::Error`として組み立てる
+    fn error_here(&self, message: impl Into<String>) -> anyhow::Error {
+        ParseError { message: message.into(), span: self.peek().span }.into()
+    }
+}
+
+fn discr(token_type: &TokenType) -> Discriminant<TokenType> {
+    std::mem::discriminant(token_type)
 }
 
 #[cfg(test)]
@@ -406,4 +546,141 @@ mod tests {
             _ => panic!("Expected if expression"),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_float_and_string_literals() {
+        let mut parser = Parser::new("3.5").unwrap();
+        assert_eq!(parser.parse().unwrap(), Expr::Float(3.5));
+
+        let mut parser = Parser::new(r#""hi""#).unwrap();
+        assert_eq!(parser.parse().unwrap(), Expr::Str("hi".to_string()));
+    }
+
+    #[test]
+    fn test_parse_bool_literals() {
+        let mut parser = Parser::new("true").unwrap();
+        assert_eq!(parser.parse().unwrap(), Expr::Bool(true));
+
+        let mut parser = Parser::new("false").unwrap();
+        assert_eq!(parser.parse().unwrap(), Expr::Bool(false));
+    }
+
+    #[test]
+    fn test_parse_logical_operators() {
+        let mut parser = Parser::new("!x && y || z").unwrap();
+        let expr = parser.parse().unwrap();
+
+        // Should parse as (!x && y) || z due to precedence
+        match expr {
+            Expr::Logical { left, op, right } => {
+                assert_eq!(op, LogicalOp::Or);
+                assert_eq!(*right, Expr::Variable("z".to_string()));
+                match *left {
+                    Expr::Logical { left, op, right } => {
+                        assert_eq!(op, LogicalOp::And);
+                        assert_eq!(
+                            *left,
+                            Expr::Unary {
+                                op: UnaryOp::Not,
+                                operand: Box::new(Expr::Variable("x".to_string())),
+                            }
+                        );
+                        assert_eq!(*right, Expr::Variable("y".to_string()));
+                    }
+                    _ => panic!("Expected logical and"),
+                }
+            }
+            _ => panic!("Expected logical or"),
+        }
+    }
+
+    #[test]
+    fn test_parse_while_with_single_statement_body() {
+        let mut parser = Parser::new("while(i < 10, i = i + 1)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        match expr {
+            Expr::While { condition, body } => {
+                assert!(matches!(*condition, Expr::Binary { op: BinaryOp::Less, .. }));
+                assert!(matches!(*body, Expr::Assignment { .. }));
+            }
+            _ => panic!("Expected while expression"),
+        }
+    }
+
+    #[test]
+    fn test_parse_while_with_sequence_body() {
+        let mut parser = Parser::new("while(i < 10, i = i + 1; sum = sum + i)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        match expr {
+            Expr::While { body, .. } => match *body {
+                Expr::Sequence(statements) => assert_eq!(statements.len(), 2),
+                _ => panic!("Expected sequence body"),
+            },
+            _ => panic!("Expected while expression"),
+        }
+    }
+
+    #[test]
+    fn test_parse_nested_call_in_expression() {
+        let mut parser = Parser::new("fib(3) + fact(2) * 2").unwrap();
+        let expr = parser.parse().unwrap();
+
+        match expr {
+            Expr::Binary { left, op, right } => {
+                assert!(matches!(*left, Expr::FunctionCall { .. }));
+                assert_eq!(op, BinaryOp::Add);
+                assert!(matches!(*right, Expr::Binary { .. }));
+            }
+            _ => panic!("Expected binary expression"),
+        }
+    }
+
+    #[test]
+    fn test_parse_nullish_coalescing_operator() {
+        let mut parser = Parser::new("x ?? 5").unwrap();
+        let expr = parser.parse().unwrap();
+
+        match expr {
+            Expr::Fallback { primary, fallback } => {
+                assert_eq!(*primary, Expr::Variable("x".to_string()));
+                assert_eq!(*fallback, Expr::Number(5));
+            }
+            _ => panic!("Expected fallback expression"),
+        }
+    }
+
+    #[test]
+    fn test_parse_default_call_syntax_is_equivalent_to_nullish() {
+        let mut parser = Parser::new("default(x, 5)").unwrap();
+        let expr = parser.parse().unwrap();
+
+        match expr {
+            Expr::Fallback { primary, fallback } => {
+                assert_eq!(*primary, Expr::Variable("x".to_string()));
+                assert_eq!(*fallback, Expr::Number(5));
+            }
+            _ => panic!("Expected fallback expression"),
+        }
+    }
+
+    #[test]
+    fn test_default_call_requires_exactly_two_arguments() {
+        let mut parser = Parser::new("default(x)").unwrap();
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn test_nullish_binds_looser_than_logical_or() {
+        let mut parser = Parser::new("a || b ?? c").unwrap();
+        let expr = parser.parse().unwrap();
+
+        match expr {
+            Expr::Fallback { primary, .. } => {
+                assert!(matches!(*primary, Expr::Logical { op: LogicalOp::Or, .. }));
+            }
+            _ => panic!("Expected fallback expression at the top level"),
+        }
+    }
+}